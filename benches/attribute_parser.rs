@@ -0,0 +1,68 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use std::time::{Duration, Instant};
+extern crate webrtc_sdp;
+
+use webrtc_sdp::attribute_type::{parse_attribute, SdpAttributeParser};
+
+// Compares the throughput of the stateless parse_attribute() with a reused
+// SdpAttributeParser. Run with `cargo bench --bench attribute_parser`.
+
+const LINES: &[&str] = &[
+    "candidate:0 1 UDP 2122252543 172.16.156.106 49760 typ host generation 0",
+    "candidate:1 1 UDP 1685987071 24.23.204.141 54609 typ srflx raddr 192.168.1.4 rport 61665",
+    "rtpmap:109 opus/48000/2",
+    "fmtp:109 maxplaybackrate=48000;stereo=1;useinbandfec=1",
+    "rtcp-fb:120 nack pli",
+    "extmap:1/sendonly urn:ietf:params:rtp-hdrext:ssrc-audio-level",
+    "fingerprint:sha-256 CD:34:D1:62:16:95:7B:B7:EB:74:E2:39:27:97:EB:0B:23:73:AC:BC:BF:2F:E3:91:CB:57:A9:9D:4A:A2:0B:40",
+    "ice-ufrag:4a799b2e",
+    "mid:sdparta_0",
+    "sendrecv",
+];
+
+const ROUNDS: usize = 20_000;
+
+fn measure<F: FnMut(&str)>(mut parse: F) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        for line in LINES {
+            parse(line);
+        }
+    }
+    start.elapsed()
+}
+
+fn report(name: &str, elapsed: Duration) {
+    let lines = (ROUNDS * LINES.len()) as f64;
+    println!(
+        "{:<22} {:>8.1} ms {:>10.0} lines/s",
+        name,
+        elapsed.as_secs_f64() * 1000.0,
+        lines / elapsed.as_secs_f64()
+    );
+}
+
+fn main() {
+    // Warm up both paths before measuring.
+    let mut parser = SdpAttributeParser::default();
+    for line in LINES {
+        assert!(parse_attribute(line).is_ok());
+        assert!(parser.parse(line).is_ok());
+    }
+
+    report(
+        "parse_attribute",
+        measure(|line| {
+            parse_attribute(line).unwrap();
+        }),
+    );
+    report(
+        "SdpAttributeParser",
+        measure(|line| {
+            parser.parse(line).unwrap();
+        }),
+    );
+}
@@ -7,6 +7,7 @@ use self::url::Host;
 use error::SdpParserInternalError;
 use std::convert::TryFrom;
 use std::fmt;
+use std::hash::{Hash, Hasher};
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use std::str::FromStr;
 
@@ -65,6 +66,24 @@ impl PartialEq for Address {
     }
 }
 
+impl Eq for Address {}
+
+// Consistent with PartialEq, which compares host names case-insensitively.
+impl Hash for Address {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            Address::Fqdn(name) => {
+                0u8.hash(state);
+                name.to_lowercase().hash(state);
+            }
+            Address::Ip(ip) => {
+                1u8.hash(state);
+                ip.hash(state);
+            }
+        }
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Debug)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 pub enum AddressType {
@@ -93,6 +112,17 @@ impl FromStr for AddressType {
     }
 }
 
+impl AddressType {
+    pub fn from_ip(ip: &IpAddr) -> AddressType {
+        ip.address_type()
+    }
+
+    /// Whether the IP address belongs to this address family.
+    pub fn matches(&self, ip: &IpAddr) -> bool {
+        *self == ip.address_type()
+    }
+}
+
 pub trait AddressTyped {
     fn address_type(&self) -> AddressType;
 }
@@ -158,7 +188,7 @@ impl TryFrom<(AddressType, &str)> for ExplicitlyTypedAddress {
     fn try_from(item: (AddressType, &str)) -> Result<Self, Self::Error> {
         match Address::from_str(item.1)? {
             Address::Ip(ip) => {
-                if ip.address_type() != item.0 {
+                if !item.0.matches(&ip) {
                     Err(SdpParserInternalError::AddressTypeMismatch {
                         found: ip.address_type(),
                         expected: item.0,
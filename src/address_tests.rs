@@ -71,3 +71,16 @@ fn test_ipv6_address_parsing() -> Result<(), ParseTestError> {
     }
     Ok(())
 }
+
+#[test]
+fn test_address_type_from_ip() -> Result<(), ParseTestError> {
+    let v4 = IpAddr::V4("192.0.2.1".parse::<Ipv4Addr>()?);
+    let v6 = IpAddr::V6("2001:db8::1".parse::<Ipv6Addr>()?);
+    assert_eq!(AddressType::from_ip(&v4), AddressType::IpV4);
+    assert_eq!(AddressType::from_ip(&v6), AddressType::IpV6);
+    assert!(AddressType::IpV4.matches(&v4));
+    assert!(!AddressType::IpV4.matches(&v6));
+    assert!(AddressType::IpV6.matches(&v6));
+    assert!(!AddressType::IpV6.matches(&v4));
+    Ok(())
+}
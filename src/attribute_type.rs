@@ -3,13 +3,16 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
 extern crate url;
+use std::collections::{BTreeSet, HashMap};
 use std::convert::TryFrom;
 use std::fmt;
 use std::iter;
+use std::net::{IpAddr, SocketAddr};
 use std::str::FromStr;
 
-use error::SdpParserInternalError;
+use error::{SdpErrorKind, SdpParserInternalError};
 use network::{parse_network_type, parse_unicast_address};
+use SdpParserConfig;
 use SdpType;
 
 use address::{Address, AddressType, ExplicitlyTypedAddress};
@@ -120,11 +123,13 @@ impl fmt::Display for SdpAttributePayloadType {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 pub enum SdpAttributeCandidateTransport {
     Udp,
     Tcp,
+    /// A transport extension token, only accepted outside of strict mode.
+    Unknown(String),
 }
 
 impl fmt::Display for SdpAttributeCandidateTransport {
@@ -132,12 +137,35 @@ impl fmt::Display for SdpAttributeCandidateTransport {
         match *self {
             SdpAttributeCandidateTransport::Udp => "UDP",
             SdpAttributeCandidateTransport::Tcp => "TCP",
+            SdpAttributeCandidateTransport::Unknown(ref transport) => transport,
         }
         .fmt(f)
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+impl FromStr for SdpAttributeCandidateTransport {
+    type Err = SdpParserInternalError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_ref() {
+            "udp" => Ok(SdpAttributeCandidateTransport::Udp),
+            "tcp" => Ok(SdpAttributeCandidateTransport::Tcp),
+            _ if s.contains('/') => Err(SdpParserInternalError::Invalid(
+                SdpErrorKind::UnknownTransport,
+                format!(
+                    "Candidate transport '{}' is a media protocol, only UDP or TCP are allowed",
+                    s
+                ),
+            )),
+            _ => Err(SdpParserInternalError::Invalid(
+                SdpErrorKind::UnknownTransport,
+                format!("Unknown candidate transport value '{}'", s),
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 pub enum SdpAttributeCandidateType {
     Host,
@@ -158,7 +186,24 @@ impl fmt::Display for SdpAttributeCandidateType {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+impl FromStr for SdpAttributeCandidateType {
+    type Err = SdpParserInternalError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_ref() {
+            "host" => Ok(SdpAttributeCandidateType::Host),
+            "srflx" => Ok(SdpAttributeCandidateType::Srflx),
+            "prflx" => Ok(SdpAttributeCandidateType::Prflx),
+            "relay" => Ok(SdpAttributeCandidateType::Relay),
+            _ => Err(SdpParserInternalError::Invalid(
+                SdpErrorKind::InvalidValue,
+                "Unknown candidate type value".to_string(),
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 pub enum SdpAttributeCandidateTcpType {
     Active,
@@ -177,14 +222,32 @@ impl fmt::Display for SdpAttributeCandidateTcpType {
     }
 }
 
-#[derive(Clone)]
+impl FromStr for SdpAttributeCandidateTcpType {
+    type Err = SdpParserInternalError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_ref() {
+            "active" => Ok(SdpAttributeCandidateTcpType::Active),
+            "passive" => Ok(SdpAttributeCandidateTcpType::Passive),
+            "so" => Ok(SdpAttributeCandidateTcpType::Simultaneous),
+            _ => Err(SdpParserInternalError::Invalid(
+                SdpErrorKind::InvalidValue,
+                "Unknown tcptype value in candidate line".to_string(),
+            )),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub struct SdpAttributeCandidate {
     pub foundation: String,
     pub component: u32,
     pub transport: SdpAttributeCandidateTransport,
-    pub priority: u64,
+    pub priority: u32,
+    /// IP addresses are kept parsed and get serialized in their canonical
+    /// form, so 2001:DB8:0::1 is written out as 2001:db8::1.
     pub address: Address,
     pub port: u32,
     pub c_type: SdpAttributeCandidateType,
@@ -193,30 +256,45 @@ pub struct SdpAttributeCandidate {
     pub tcp_type: Option<SdpAttributeCandidateTcpType>,
     pub generation: Option<u32>,
     pub ufrag: Option<String>,
-    pub networkcost: Option<u32>,
+    pub networkcost: Option<u16>,
+    pub networkid: Option<u16>,
     pub unknown_extensions: Vec<(String, String)>,
+    pub resolved_address: Option<IpAddr>,
+    /// Zone id of a link-local IPv6 address, like eth0 in fe80::1%eth0.
+    pub scope: Option<String>,
 }
 
 impl fmt::Display for SdpAttributeCandidate {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Host candidates have no related address and the parser rejects
+        // them if they carry one, so never emit one for them.
+        let (raddr, rport) = match self.c_type {
+            SdpAttributeCandidateType::Host => (String::new(), String::new()),
+            _ => (
+                option_to_string!(" raddr {}", self.raddr),
+                option_to_string!(" rport {}", self.rport),
+            ),
+        };
         write!(
             f,
             "{foundation} {component} {transport} {priority} \
-             {address} {port} typ {ctype}\
-             {raddr}{rport}{tcp_type}{generation}{ufrag}{cost}\
+             {address}{scope} {port} typ {ctype}\
+             {raddr}{rport}{tcp_type}{generation}{ufrag}{id}{cost}\
              {unknown}",
             foundation = self.foundation,
             component = self.component,
             transport = self.transport,
             priority = self.priority,
             address = self.address,
+            scope = option_to_string!("%{}", self.scope),
             port = self.port,
             ctype = self.c_type,
-            raddr = option_to_string!(" raddr {}", self.raddr),
-            rport = option_to_string!(" rport {}", self.rport),
+            raddr = raddr,
+            rport = rport,
             tcp_type = option_to_string!(" tcptype {}", self.tcp_type),
             generation = option_to_string!(" generation {}", self.generation),
             ufrag = option_to_string!(" ufrag {}", self.ufrag),
+            id = option_to_string!(" network-id {}", self.networkid),
             cost = option_to_string!(" network-cost {}", self.networkcost),
             unknown = self
                 .unknown_extensions
@@ -232,7 +310,7 @@ impl SdpAttributeCandidate {
         foundation: String,
         component: u32,
         transport: SdpAttributeCandidateTransport,
-        priority: u64,
+        priority: u32,
         address: Address,
         port: u32,
         c_type: SdpAttributeCandidateType,
@@ -251,7 +329,83 @@ impl SdpAttributeCandidate {
             generation: None,
             ufrag: None,
             networkcost: None,
+            networkid: None,
             unknown_extensions: Vec::new(),
+            resolved_address: None,
+            scope: None,
+        }
+    }
+
+    /// The address and port of the candidate as a socket address. Host names
+    /// are only supported if they got resolved during parsing.
+    pub fn socket_addr(&self) -> Option<SocketAddr> {
+        let ip = match self.address {
+            Address::Ip(ip) => ip,
+            Address::Fqdn(_) => self.resolved_address?,
+        };
+        Some(SocketAddr::new(ip, u16::try_from(self.port).ok()?))
+    }
+
+    /// The related address and port (raddr and rport) as a socket address.
+    pub fn remote_socket_addr(&self) -> Option<SocketAddr> {
+        match (&self.raddr, self.rport) {
+            (Some(Address::Ip(ip)), Some(port)) => {
+                Some(SocketAddr::new(*ip, u16::try_from(port).ok()?))
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether this is a placeholder candidate, which uses port 0 and an
+    /// unspecified address like 0.0.0.0 or ::.
+    pub fn is_placeholder(&self) -> bool {
+        match self.address {
+            Address::Ip(ip) => self.port == 0 && ip.is_unspecified(),
+            Address::Fqdn(_) => false,
+        }
+    }
+
+    /// Whether the related address is unspecified, like 0.0.0.0 or ::, which
+    /// relay candidates use to hide the address of the client.
+    pub fn has_unspecified_related_address(&self) -> bool {
+        match self.raddr {
+            Some(Address::Ip(ip)) => ip.is_unspecified(),
+            _ => false,
+        }
+    }
+
+    /// Whether the candidate can be used by a data channel m-section. Data
+    /// channels run DTLS/SCTP over UDP or TCP on a single component, so only
+    /// candidates of component 1 qualify.
+    pub fn can_carry_data_channel(&self) -> bool {
+        self.component == 1
+    }
+
+    /// Whether both candidates describe the same transport address of the
+    /// same foundation and component, which makes one of them redundant as
+    /// described in RFC8445 section 5.1.3. Priorities are not compared.
+    pub fn is_redundant_with(&self, other: &Self) -> bool {
+        self.foundation == other.foundation
+            && self.component == other.component
+            && self.transport == other.transport
+            && self.address == other.address
+            && self.port == other.port
+    }
+
+    pub fn to_ice_candidate(&self) -> IceCandidate {
+        IceCandidate {
+            foundation: self.foundation.clone(),
+            component: self.component,
+            protocol: self.transport.to_string().to_lowercase(),
+            priority: self.priority,
+            address: self.address.to_string(),
+            port: self.port,
+            typ: self.c_type.to_string(),
+            related: match (&self.raddr, self.rport) {
+                (Some(addr), Some(port)) => Some((addr.to_string(), port)),
+                _ => None,
+            },
+            tcp_type: self.tcp_type.as_ref().map(ToString::to_string),
         }
     }
 
@@ -275,13 +429,62 @@ impl SdpAttributeCandidate {
         self.ufrag = Some(u)
     }
 
-    fn set_network_cost(&mut self, n: u32) {
+    fn set_network_cost(&mut self, n: u16) {
         self.networkcost = Some(n)
     }
 
+    fn set_network_id(&mut self, n: u16) {
+        self.networkid = Some(n)
+    }
+
+    /// The ICE generation the candidate belongs to, which gets incremented
+    /// with every ICE restart.
+    pub fn generation(&self) -> Option<u32> {
+        self.generation
+    }
+
+    pub fn network_cost(&self) -> Option<u16> {
+        self.networkcost
+    }
+
+    pub fn network_id(&self) -> Option<u16> {
+        self.networkid
+    }
+
+    /// The ICE username fragment the candidate belongs to, which allows to
+    /// match trickled candidates with the credentials of an ICE generation.
+    pub fn ufrag(&self) -> Option<&str> {
+        self.ufrag.as_deref()
+    }
+
     fn add_unknown_extension(&mut self, name: String, value: String) {
         self.unknown_extensions.push((name, value));
     }
+
+    fn set_resolved_address(&mut self, ip: IpAddr) {
+        self.resolved_address = Some(ip)
+    }
+}
+
+/// Plain representation of a candidate to be handed to an ICE agent, which
+/// does not depend on the SDP specific types of this crate.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+pub struct IceCandidate {
+    pub foundation: String,
+    pub component: u32,
+    /// Lowercase transport protocol, usually "udp" or "tcp". Transport
+    /// extensions accepted outside of strict mode are passed through too.
+    pub protocol: String,
+    pub priority: u32,
+    pub address: String,
+    pub port: u32,
+    /// Candidate type, like "host" or "srflx".
+    pub typ: String,
+    /// The related address and port.
+    pub related: Option<(String, u32)>,
+    /// TCP candidate type, like "active" or "passive".
+    pub tcp_type: Option<String>,
 }
 
 impl AnonymizingClone for SdpAttributeCandidate {
@@ -294,11 +497,13 @@ impl AnonymizingClone for SdpAttributeCandidate {
             .clone()
             .map(|addr| anonymizer.mask_address(&addr));
         masked.rport = self.rport.map(|port| anonymizer.mask_port(port));
+        masked.resolved_address = self.resolved_address.map(|ip| anonymizer.mask_ip(&ip));
+        masked.scope = None;
         masked
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub enum SdpAttributeDtlsMessage {
@@ -316,7 +521,7 @@ impl fmt::Display for SdpAttributeDtlsMessage {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub struct SdpAttributeRemoteCandidate {
@@ -347,7 +552,7 @@ impl AnonymizingClone for SdpAttributeRemoteCandidate {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub struct SdpAttributeSimulcastId {
@@ -381,7 +586,7 @@ impl fmt::Display for SdpAttributeSimulcastId {
 }
 
 #[repr(C)]
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub struct SdpAttributeSimulcastVersion {
@@ -393,7 +598,7 @@ impl SdpAttributeSimulcastVersion {
         SdpAttributeSimulcastVersion {
             ids: idlist
                 .split(',')
-                .map(SdpAttributeSimulcastId::new)
+                .map(|id| SdpAttributeSimulcastId::new(id.trim()))
                 .collect(),
         }
     }
@@ -410,7 +615,7 @@ impl fmt::Display for SdpAttributeSimulcastVersion {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub struct SdpAttributeSimulcast {
@@ -429,7 +634,7 @@ impl fmt::Display for SdpAttributeSimulcast {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub struct SdpAttributeRtcp {
@@ -459,14 +664,14 @@ impl fmt::Display for SdpAttributeRtcp {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub enum SdpAttributeRtcpFbType {
+    // The explicit values match the enum used in the glue-code
     Ack = 0,
-    Ccm = 2, // This is explicitly 2 to make the conversion to the
-    // enum used in the glue-code possible. The glue code has "app"
-    // in the place of 1
+    App = 1,
+    Ccm = 2,
     Nack,
     TrrInt,
     Remb,
@@ -477,6 +682,7 @@ impl fmt::Display for SdpAttributeRtcpFbType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             SdpAttributeRtcpFbType::Ack => "ack",
+            SdpAttributeRtcpFbType::App => "app",
             SdpAttributeRtcpFbType::Ccm => "ccm",
             SdpAttributeRtcpFbType::Nack => "nack",
             SdpAttributeRtcpFbType::TrrInt => "trr-int",
@@ -487,7 +693,7 @@ impl fmt::Display for SdpAttributeRtcpFbType {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub struct SdpAttributeRtcpFb {
@@ -512,13 +718,14 @@ impl fmt::Display for SdpAttributeRtcpFb {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub enum SdpAttributeDirection {
     Recvonly,
     Sendonly,
     Sendrecv,
+    Inactive,
 }
 
 impl fmt::Display for SdpAttributeDirection {
@@ -527,12 +734,30 @@ impl fmt::Display for SdpAttributeDirection {
             SdpAttributeDirection::Recvonly => "recvonly",
             SdpAttributeDirection::Sendonly => "sendonly",
             SdpAttributeDirection::Sendrecv => "sendrecv",
+            SdpAttributeDirection::Inactive => "inactive",
         }
         .fmt(f)
     }
 }
 
-#[derive(Clone)]
+impl FromStr for SdpAttributeDirection {
+    type Err = SdpParserInternalError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_ref() {
+            "recvonly" => Ok(SdpAttributeDirection::Recvonly),
+            "sendonly" => Ok(SdpAttributeDirection::Sendonly),
+            "sendrecv" => Ok(SdpAttributeDirection::Sendrecv),
+            "inactive" => Ok(SdpAttributeDirection::Inactive),
+            _ => Err(SdpParserInternalError::Invalid(
+                SdpErrorKind::InvalidValue,
+                format!("Unsupported direction '{}'", s),
+            )),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub struct SdpAttributeExtmap {
@@ -555,7 +780,91 @@ impl fmt::Display for SdpAttributeExtmap {
     }
 }
 
-#[derive(Clone, Copy)]
+impl SdpAttributeExtmap {
+    pub fn header_extension(&self) -> SdpRtpHeaderExtension {
+        SdpRtpHeaderExtension::from(self.url.as_str())
+    }
+
+    /// The direction of the extension, which defaults to sendrecv if it is
+    /// omitted.
+    pub fn effective_direction(&self) -> SdpAttributeDirection {
+        self.direction
+            .clone()
+            .unwrap_or(SdpAttributeDirection::Sendrecv)
+    }
+
+    /// Ids above 14 only fit into the two-byte header format of RFC8285.
+    pub fn requires_two_byte_header(&self) -> bool {
+        self.id > 14
+    }
+}
+
+/// Commonly used RTP header extensions, as identified by their extmap URI.
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "enhanced_debug", derive(Debug))]
+pub enum SdpRtpHeaderExtension {
+    SsrcAudioLevel,         // RFC6464
+    AbsSendTime,            // webrtc.org experiment
+    TransmissionTimeOffset, // RFC5450
+    SdesMid,                // RFC8843
+    SdesRtpStreamId,        // RFC8852
+    TransportWideCc,        // draft-holmer-rmcat-transport-wide-cc-extensions
+    Other(String),
+}
+
+const RTP_HEADER_EXTENSION_URIS: [(&str, SdpRtpHeaderExtension); 6] = [
+    (
+        "urn:ietf:params:rtp-hdrext:ssrc-audio-level",
+        SdpRtpHeaderExtension::SsrcAudioLevel,
+    ),
+    (
+        "http://www.webrtc.org/experiments/rtp-hdrext/abs-send-time",
+        SdpRtpHeaderExtension::AbsSendTime,
+    ),
+    (
+        "urn:ietf:params:rtp-hdrext:toffset",
+        SdpRtpHeaderExtension::TransmissionTimeOffset,
+    ),
+    (
+        "urn:ietf:params:rtp-hdrext:sdes:mid",
+        SdpRtpHeaderExtension::SdesMid,
+    ),
+    (
+        "urn:ietf:params:rtp-hdrext:sdes:rtp-stream-id",
+        SdpRtpHeaderExtension::SdesRtpStreamId,
+    ),
+    (
+        "http://www.ietf.org/id/draft-holmer-rmcat-transport-wide-cc-extensions-01",
+        SdpRtpHeaderExtension::TransportWideCc,
+    ),
+];
+
+impl From<&str> for SdpRtpHeaderExtension {
+    fn from(uri: &str) -> Self {
+        RTP_HEADER_EXTENSION_URIS
+            .iter()
+            .find(|(known, _)| known.eq_ignore_ascii_case(uri))
+            .map(|(_, extension)| extension.clone())
+            .unwrap_or_else(|| SdpRtpHeaderExtension::Other(uri.to_string()))
+    }
+}
+
+impl fmt::Display for SdpRtpHeaderExtension {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SdpRtpHeaderExtension::Other(ref uri) => uri.fmt(f),
+            ref known => RTP_HEADER_EXTENSION_URIS
+                .iter()
+                .find(|(_, extension)| extension == known)
+                .map(|(uri, _)| *uri)
+                .unwrap_or_default()
+                .fmt(f),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub struct RtxFmtpParameters {
@@ -573,7 +882,7 @@ impl fmt::Display for RtxFmtpParameters {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub struct SdpAttributeFmtpParameters {
@@ -593,6 +902,8 @@ pub struct SdpAttributeFmtpParameters {
     // VP8 and VP9
     // max_fs, already defined in H264
     pub max_fr: u32,
+    // VP9 only, https://tools.ietf.org/html/draft-ietf-payload-vp9
+    pub profile_id: Option<u32>,
 
     // Opus https://tools.ietf.org/html/rfc7587
     pub maxplaybackrate: u32,
@@ -618,6 +929,36 @@ pub struct SdpAttributeFmtpParameters {
     pub unknown_tokens: Vec<String>,
 }
 
+impl Default for SdpAttributeFmtpParameters {
+    fn default() -> Self {
+        SdpAttributeFmtpParameters {
+            packetization_mode: 0,
+            level_asymmetry_allowed: false,
+            profile_level_id: 0x0042_0010,
+            max_fs: 0,
+            max_cpb: 0,
+            max_dpb: 0,
+            max_br: 0,
+            max_mbps: 0,
+            usedtx: false,
+            stereo: false,
+            useinbandfec: false,
+            cbr: false,
+            max_fr: 0,
+            profile_id: None,
+            maxplaybackrate: 48000,
+            maxaveragebitrate: 0,
+            ptime: 0,
+            minptime: 0,
+            maxptime: 0,
+            encodings: Vec::new(),
+            dtmf_tones: "".to_string(),
+            rtx: None,
+            unknown_tokens: Vec::new(),
+        }
+    }
+}
+
 impl fmt::Display for SdpAttributeFmtpParameters {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         if let Some(ref rtx) = self.rtx {
@@ -658,6 +999,7 @@ impl fmt::Display for SdpAttributeFmtpParameters {
                 maybe_print_param("max-br=", self.max_br, 0),
                 maybe_print_param("max-mbps=", self.max_mbps, 0),
                 maybe_print_param("max-fr=", self.max_fr, 0),
+                option_to_string!("profile-id={}", self.profile_id),
                 maybe_print_param("maxplaybackrate=", self.maxplaybackrate, 48000),
                 maybe_print_param("maxaveragebitrate=", self.maxaveragebitrate, 0),
                 maybe_print_param("ptime=", self.ptime, 0),
@@ -667,19 +1009,83 @@ impl fmt::Display for SdpAttributeFmtpParameters {
                 maybe_print_bool_param("stereo", self.stereo, false),
                 maybe_print_bool_param("useinbandfec", self.useinbandfec, false),
                 maybe_print_bool_param("cbr", self.cbr, false),
-                maybe_vector_to_string!("{}", self.unknown_tokens, ",")
+                maybe_vector_to_string!("{}", self.unknown_tokens, ";")
             ]
             .join(";")
         )
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub struct SdpAttributeFmtp {
-    pub payload_type: u8,
+    pub payload_type: SdpAttributePayloadType,
     pub parameters: SdpAttributeFmtpParameters,
+    // The ';' separated tokens of the parameter block
+    pub tokens: Vec<String>,
+}
+
+impl SdpAttributeFmtp {
+    // Builds the fmtp from the raw parameter tokens, which get parsed into
+    // the structured parameters.
+    pub fn new(
+        payload_type: SdpAttributePayloadType,
+        tokens: Vec<String>,
+    ) -> Result<Self, SdpParserInternalError> {
+        if tokens.is_empty() {
+            return Err(SdpParserInternalError::Invalid(
+                SdpErrorKind::MissingToken,
+                "Fmtp attributes require at least one parameter token".to_string(),
+            ));
+        }
+        let parameters = parse_fmtp_parameters(&tokens.join(";"))?;
+        Ok(SdpAttributeFmtp {
+            payload_type,
+            parameters,
+            tokens,
+        })
+    }
+
+    // Builds the fmtp from structured parameters, the tokens are taken from
+    // their serialization.
+    pub fn from_parameters(
+        payload_type: SdpAttributePayloadType,
+        parameters: SdpAttributeFmtpParameters,
+    ) -> Self {
+        let tokens = split_fmtp_tokens(&parameters.to_string());
+        SdpAttributeFmtp {
+            payload_type,
+            parameters,
+            tokens,
+        }
+    }
+
+    pub fn payload_type(&self) -> &SdpAttributePayloadType {
+        &self.payload_type
+    }
+
+    pub fn parameters(&self) -> &[String] {
+        &self.tokens
+    }
+
+    pub fn parsed_parameters(&self) -> &SdpAttributeFmtpParameters {
+        &self.parameters
+    }
+
+    // Every name=value pair and bare flag of the tokens, including the ones
+    // parsed into the structured parameters. Telephone event and red lists
+    // show up as a single flag. If a name is repeated the last value wins,
+    // while the tokens keep every occurrence.
+    pub fn all_parameters(&self) -> HashMap<String, Option<String>> {
+        self.tokens
+            .iter()
+            .map(|token| match token.split_once('=') {
+                Some((name, value)) => (name.trim().to_string(), Some(value.trim().to_string())),
+                None => (token.trim().to_string(), None),
+            })
+            .collect()
+    }
 }
 
 impl fmt::Display for SdpAttributeFmtp {
@@ -693,7 +1099,7 @@ impl fmt::Display for SdpAttributeFmtp {
     }
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub enum SdpAttributeFingerprintHashType {
@@ -717,7 +1123,7 @@ impl fmt::Display for SdpAttributeFingerprintHashType {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub struct SdpAttributeFingerprint {
@@ -725,6 +1131,22 @@ pub struct SdpAttributeFingerprint {
     pub fingerprint: Vec<u8>,
 }
 
+impl SdpAttributeFingerprint {
+    /// Compares the fingerprint against the digest of a certificate. The
+    /// comparison runs in constant time with respect to the content of the
+    /// digest, only a length mismatch returns early.
+    pub fn matches(&self, digest: &[u8]) -> bool {
+        if self.fingerprint.len() != digest.len() {
+            return false;
+        }
+        self.fingerprint
+            .iter()
+            .zip(digest)
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+    }
+}
+
 impl fmt::Display for SdpAttributeFingerprint {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
@@ -863,7 +1285,7 @@ impl fmt::Display for SdpAttributeImageAttrSetList {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub struct SdpAttributeImageAttr {
@@ -887,7 +1309,7 @@ impl fmt::Display for SdpAttributeImageAttr {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub struct SdpAttributeSctpmap {
@@ -906,7 +1328,7 @@ impl fmt::Display for SdpAttributeSctpmap {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub enum SdpAttributeGroupSemantic {
@@ -934,7 +1356,7 @@ impl fmt::Display for SdpAttributeGroupSemantic {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub struct SdpAttributeGroup {
@@ -942,6 +1364,21 @@ pub struct SdpAttributeGroup {
     pub tags: Vec<String>,
 }
 
+impl SdpAttributeGroup {
+    /// Compares the groups while ignoring the order of their tags.
+    pub fn semantically_eq(&self, other: &SdpAttributeGroup) -> bool {
+        self.semantics == other.semantics && same_elements(&self.tags, &other.tags)
+    }
+}
+
+fn same_elements<T: Clone + Ord>(a: &[T], b: &[T]) -> bool {
+    let mut a = a.to_vec();
+    let mut b = b.to_vec();
+    a.sort();
+    b.sort();
+    a == b
+}
+
 impl fmt::Display for SdpAttributeGroup {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
@@ -953,11 +1390,30 @@ impl fmt::Display for SdpAttributeGroup {
     }
 }
 
-#[derive(Clone)]
+/// The stream id of an msid attribute. RFC8830 uses "-" to signal that
+/// the track does not belong to any MediaStream.
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "enhanced_debug", derive(Debug))]
+pub enum MsidId {
+    NoStream,
+    Id(String),
+}
+
+impl fmt::Display for MsidId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            MsidId::NoStream => "-".fmt(f),
+            MsidId::Id(ref id) => id.fmt(f),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub struct SdpAttributeMsid {
-    pub id: String,
+    pub id: MsidId,
     pub appdata: Option<String>,
 }
 
@@ -968,7 +1424,7 @@ impl fmt::Display for SdpAttributeMsid {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 pub struct SdpAttributeMsidSemantic {
     pub semantic: String,
@@ -985,7 +1441,7 @@ impl fmt::Display for SdpAttributeMsidSemantic {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub struct SdpAttributeRidParameters {
@@ -1015,7 +1471,7 @@ impl fmt::Display for SdpAttributeRidParameters {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub struct SdpAttributeRid {
@@ -1048,13 +1504,13 @@ impl fmt::Display for SdpAttributeRid {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub struct SdpAttributeRtpmap {
     pub payload_type: u8,
     pub codec_name: String,
-    pub frequency: u32,
+    pub frequency: Option<u32>,
     pub channels: Option<u32>,
 }
 
@@ -1063,7 +1519,7 @@ impl SdpAttributeRtpmap {
         SdpAttributeRtpmap {
             payload_type,
             codec_name,
-            frequency,
+            frequency: Some(frequency),
             channels: None,
         }
     }
@@ -1071,22 +1527,45 @@ impl SdpAttributeRtpmap {
     fn set_channels(&mut self, c: u32) {
         self.channels = Some(c)
     }
+
+    // Compares the codec name case-insensitively as required by RFC4855,
+    // the stored name keeps its original case.
+    pub fn codec_matches(&self, name: &str) -> bool {
+        self.codec_name.eq_ignore_ascii_case(name)
+    }
+
+    // Returns the clock rate of the codec. If it was omitted the clock rate
+    // implied by the static payload type assignment of RFC3551 is returned
+    // (if there is one).
+    pub fn effective_frequency(&self) -> Option<u32> {
+        if self.frequency.is_some() {
+            return self.frequency;
+        }
+        match self.payload_type {
+            0 | 3 | 4 | 5 | 7 | 8 | 9 | 12 | 13 | 15 | 18 => Some(8000),
+            6 => Some(16000),
+            10 | 11 => Some(44100),
+            16 => Some(11025),
+            17 => Some(22050),
+            14 | 25 | 26 | 28 | 31 | 32 | 33 | 34 => Some(90000),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Display for SdpAttributeRtpmap {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
-            f,
-            "{pt} {name}/{freq}",
-            pt = self.payload_type,
-            name = self.codec_name,
-            freq = self.frequency
-        )?;
-        write_option_string!(f, "/{}", self.channels)
+        write!(f, "{} {}", self.payload_type, self.codec_name)?;
+        if let Some(frequency) = self.frequency {
+            // Channels can only follow a clock rate
+            write!(f, "/{}", frequency)?;
+            write_option_string!(f, "/{}", self.channels)?;
+        }
+        Ok(())
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub enum SdpAttributeSetup {
@@ -1108,7 +1587,7 @@ impl fmt::Display for SdpAttributeSetup {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub struct SdpAttributeSsrc {
@@ -1135,6 +1614,26 @@ impl SdpAttributeSsrc {
             self.value = Some(v[1].to_string());
         }
     }
+
+    fn value_of(&self, name: &str) -> Option<&str> {
+        match (&self.attribute, &self.value) {
+            (Some(ref attribute), Some(ref value)) if attribute.eq_ignore_ascii_case(name) => {
+                Some(value.as_str())
+            }
+            _ => None,
+        }
+    }
+
+    /// The ssrc this one replaces, as signaled by "previous-ssrc".
+    pub fn previous_ssrc(&self) -> Option<u32> {
+        self.value_of("previous-ssrc")
+            .and_then(|value| value.trim().parse().ok())
+    }
+
+    /// The format parameters signaled by the "fmtp" ssrc attribute.
+    pub fn fmtp(&self) -> Option<&str> {
+        self.value_of("fmtp")
+    }
 }
 
 impl fmt::Display for SdpAttributeSsrc {
@@ -1161,7 +1660,7 @@ impl AnonymizingClone for SdpAttributeSsrc {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub enum SdpSsrcGroupSemantic {
@@ -1185,7 +1684,106 @@ impl fmt::Display for SdpSsrcGroupSemantic {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "enhanced_debug", derive(Debug))]
+pub enum SdpAttributeTsRefclk {
+    // Either a NTP server address or "/traceable/"
+    Ntp(String),
+    // A grandmaster id of None stands for "traceable"
+    Ptp {
+        version: String,
+        grandmaster: Option<String>,
+        domain: Option<u8>,
+    },
+    Local,
+}
+
+impl fmt::Display for SdpAttributeTsRefclk {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SdpAttributeTsRefclk::Ntp(ref server) => write!(f, "ntp={}", server),
+            SdpAttributeTsRefclk::Ptp {
+                ref version,
+                ref grandmaster,
+                domain,
+            } => {
+                write!(f, "ptp={}:", version)?;
+                match *grandmaster {
+                    Some(ref gmid) => write!(f, "{}", gmid)?,
+                    None => write!(f, "traceable")?,
+                }
+                write_option_string!(f, ":{}", domain)
+            }
+            SdpAttributeTsRefclk::Local => write!(f, "local"),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "enhanced_debug", derive(Debug))]
+pub enum SdpAttributeMediaClk {
+    Direct {
+        offset: Option<u64>,
+        rate: Option<(u32, u32)>,
+    },
+    Sender,
+}
+
+impl fmt::Display for SdpAttributeMediaClk {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SdpAttributeMediaClk::Direct { offset, rate } => {
+                write!(f, "direct")?;
+                write_option_string!(f, "={}", offset)?;
+                match rate {
+                    Some((numerator, denominator)) => {
+                        write!(f, " rate={}/{}", numerator, denominator)
+                    }
+                    None => Ok(()),
+                }
+            }
+            SdpAttributeMediaClk::Sender => write!(f, "sender"),
+        }
+    }
+}
+
+/// The ice-options tokens with a defined meaning.
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "enhanced_debug", derive(Debug))]
+pub enum SdpIceOption {
+    Trickle,      // RFC8840
+    Renomination, // draft-thatcher-ice-renomination
+    Ice2,         // RFC8445
+    Other(String),
+}
+
+impl From<&str> for SdpIceOption {
+    fn from(token: &str) -> Self {
+        match token.to_lowercase().as_ref() {
+            "trickle" => SdpIceOption::Trickle,
+            "renomination" => SdpIceOption::Renomination,
+            "ice2" => SdpIceOption::Ice2,
+            _ => SdpIceOption::Other(token.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for SdpIceOption {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SdpIceOption::Trickle => "trickle",
+            SdpIceOption::Renomination => "renomination",
+            SdpIceOption::Ice2 => "ice2",
+            SdpIceOption::Other(ref token) => token,
+        }
+        .fmt(f)
+    }
+}
+
+#[derive(Clone, PartialEq)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
 pub enum SdpAttribute {
@@ -1197,6 +1795,7 @@ pub enum SdpAttribute {
     ExtmapAllowMixed,
     Fingerprint(SdpAttributeFingerprint),
     Fmtp(SdpAttributeFmtp),
+    Framerate(f64),
     Group(SdpAttributeGroup),
     IceLite,
     IceMismatch,
@@ -1209,7 +1808,9 @@ pub enum SdpAttribute {
     Inactive,
     Label(String),
     MaxMessageSize(u64),
+    MaxPrate(f64),
     MaxPtime(u64),
+    MediaClk(SdpAttributeMediaClk),
     Mid(String),
     Msid(SdpAttributeMsid),
     MsidSemantic(SdpAttributeMsidSemantic),
@@ -1230,14 +1831,127 @@ pub enum SdpAttribute {
     Simulcast(SdpAttributeSimulcast),
     Ssrc(SdpAttributeSsrc),
     SsrcGroup(SdpSsrcGroupSemantic, Vec<SdpAttributeSsrc>),
+    TsRefclk(SdpAttributeTsRefclk),
 }
 
 impl SdpAttribute {
+    /// Creates a value-less attribute like rtcp-mux. Attribute types which
+    /// require a value are rejected.
+    pub fn flag(kind: SdpAttributeType) -> Result<SdpAttribute, SdpParserInternalError> {
+        match kind {
+            SdpAttributeType::BundleOnly => Ok(SdpAttribute::BundleOnly),
+            SdpAttributeType::EndOfCandidates => Ok(SdpAttribute::EndOfCandidates),
+            SdpAttributeType::ExtmapAllowMixed => Ok(SdpAttribute::ExtmapAllowMixed),
+            SdpAttributeType::IceLite => Ok(SdpAttribute::IceLite),
+            SdpAttributeType::IceMismatch => Ok(SdpAttribute::IceMismatch),
+            SdpAttributeType::Inactive => Ok(SdpAttribute::Inactive),
+            SdpAttributeType::Recvonly => Ok(SdpAttribute::Recvonly),
+            SdpAttributeType::RtcpMux => Ok(SdpAttribute::RtcpMux),
+            SdpAttributeType::RtcpRsize => Ok(SdpAttribute::RtcpRsize),
+            SdpAttributeType::Sendonly => Ok(SdpAttribute::Sendonly),
+            SdpAttributeType::Sendrecv => Ok(SdpAttribute::Sendrecv),
+            _ => Err(SdpParserInternalError::Invalid(
+                SdpErrorKind::MissingToken,
+                format!("{} attribute requires a value", kind),
+            )),
+        }
+    }
+
+    /// Compares the attributes while ignoring the order of the tags of group
+    /// attributes and of the ssrcs of ssrc-group attributes.
+    pub fn semantically_eq(&self, other: &SdpAttribute) -> bool {
+        match (self, other) {
+            (SdpAttribute::Group(ref a), SdpAttribute::Group(ref b)) => a.semantically_eq(b),
+            (
+                SdpAttribute::SsrcGroup(ref semantic_a, ref ssrcs_a),
+                SdpAttribute::SsrcGroup(ref semantic_b, ref ssrcs_b),
+            ) => {
+                let ids = |ssrcs: &[SdpAttributeSsrc]| -> Vec<u32> {
+                    ssrcs.iter().map(|ssrc| ssrc.id).collect()
+                };
+                semantic_a == semantic_b && same_elements(&ids(ssrcs_a), &ids(ssrcs_b))
+            }
+            _ => self.to_string() == other.to_string(),
+        }
+    }
+
+    /// Returns a normalized copy of the attribute, which allows to diff SDPs
+    /// semantically: unknown fmtp parameters, group tags and ssrc-group
+    /// ssrcs get sorted, codec names and host names get lowercased and
+    /// candidate transport extensions get uppercased.
+    pub fn canonicalize(&self) -> SdpAttribute {
+        let lowercase_fqdn = |address: &mut Address| {
+            if let Address::Fqdn(ref mut name) = *address {
+                *name = name.to_lowercase();
+            }
+        };
+        let mut canonical = self.clone();
+        match canonical {
+            SdpAttribute::Candidate(ref mut candidate) => {
+                if let SdpAttributeCandidateTransport::Unknown(ref mut transport) =
+                    candidate.transport
+                {
+                    *transport = transport.to_uppercase();
+                }
+                lowercase_fqdn(&mut candidate.address);
+                if let Some(ref mut raddr) = candidate.raddr {
+                    lowercase_fqdn(raddr);
+                }
+            }
+            SdpAttribute::Fmtp(ref mut fmtp) => {
+                fmtp.parameters.unknown_tokens.sort();
+                fmtp.tokens = split_fmtp_tokens(&fmtp.parameters.to_string());
+            }
+            SdpAttribute::Group(ref mut group) => group.tags.sort(),
+            SdpAttribute::Rtpmap(ref mut rtpmap) => {
+                rtpmap.codec_name = rtpmap.codec_name.to_lowercase()
+            }
+            SdpAttribute::SsrcGroup(_, ref mut ssrcs) => ssrcs.sort_by_key(|ssrc| ssrc.id),
+            _ => (),
+        }
+        canonical
+    }
+
+    pub fn mid(&self) -> Option<&str> {
+        match *self {
+            SdpAttribute::Mid(ref mid) => Some(mid),
+            _ => None,
+        }
+    }
+
+    /// The SCTP port of either a sctp-port or a legacy sctpmap attribute.
+    pub fn sctp_port(&self) -> Option<u16> {
+        match *self {
+            SdpAttribute::SctpPort(port) => u16::try_from(port).ok(),
+            SdpAttribute::Sctpmap(ref sctpmap) => Some(sctpmap.port),
+            _ => None,
+        }
+    }
+
+    pub fn is_kind(&self, kind: &SdpAttributeType) -> bool {
+        SdpAttributeType::from(self) == *kind
+    }
+
+    /// The typed tokens of an ice-options attribute, None for all other
+    /// attributes.
+    pub fn ice_options(&self) -> Option<Vec<SdpIceOption>> {
+        match *self {
+            SdpAttribute::IceOptions(ref options) => Some(
+                options
+                    .iter()
+                    .map(|option| SdpIceOption::from(option.as_str()))
+                    .collect(),
+            ),
+            _ => None,
+        }
+    }
+
     pub fn allowed_at_session_level(&self) -> bool {
         match *self {
             SdpAttribute::BundleOnly
             | SdpAttribute::Candidate(..)
             | SdpAttribute::Fmtp(..)
+            | SdpAttribute::Framerate(..)
             | SdpAttribute::IceMismatch
             | SdpAttribute::ImageAttr(..)
             | SdpAttribute::Label(..)
@@ -1272,11 +1986,14 @@ impl SdpAttribute {
             | SdpAttribute::IceUfrag(..)
             | SdpAttribute::Identity(..)
             | SdpAttribute::Inactive
+            | SdpAttribute::MaxPrate(..)
+            | SdpAttribute::MediaClk(..)
             | SdpAttribute::MsidSemantic(..)
             | SdpAttribute::Recvonly
             | SdpAttribute::Sendonly
             | SdpAttribute::Sendrecv
-            | SdpAttribute::Setup(..) => true,
+            | SdpAttribute::Setup(..)
+            | SdpAttribute::TsRefclk(..) => true,
         }
     }
 
@@ -1296,6 +2013,7 @@ impl SdpAttribute {
             | SdpAttribute::ExtmapAllowMixed
             | SdpAttribute::Fingerprint(..)
             | SdpAttribute::Fmtp(..)
+            | SdpAttribute::Framerate(..)
             | SdpAttribute::IceMismatch
             | SdpAttribute::IceOptions(..)
             | SdpAttribute::IcePwd(..)
@@ -1304,7 +2022,9 @@ impl SdpAttribute {
             | SdpAttribute::Inactive
             | SdpAttribute::Label(..)
             | SdpAttribute::MaxMessageSize(..)
+            | SdpAttribute::MaxPrate(..)
             | SdpAttribute::MaxPtime(..)
+            | SdpAttribute::MediaClk(..)
             | SdpAttribute::Mid(..)
             | SdpAttribute::Msid(..)
             | SdpAttribute::Ptime(..)
@@ -1323,7 +2043,8 @@ impl SdpAttribute {
             | SdpAttribute::Setup(..)
             | SdpAttribute::Simulcast(..)
             | SdpAttribute::Ssrc(..)
-            | SdpAttribute::SsrcGroup(..) => true,
+            | SdpAttribute::SsrcGroup(..)
+            | SdpAttribute::TsRefclk(..) => true,
         }
     }
 }
@@ -1332,73 +2053,147 @@ impl FromStr for SdpAttribute {
     type Err = SdpParserInternalError;
 
     fn from_str(line: &str) -> Result<Self, Self::Err> {
-        let tokens: Vec<_> = line.splitn(2, ':').collect();
-        let name = tokens[0].to_lowercase();
-        let val = match tokens.get(1) {
-            Some(x) => x.trim(),
-            None => "",
-        };
-        if tokens.len() > 1 {
-            match name.as_str() {
-                "bundle-only" | "end-of-candidates" | "extmap-allow-mixed" | "ice-lite"
-                | "ice-mismatch" | "inactive" | "recvonly" | "rtcp-mux" | "rtcp-rsize"
-                | "sendonly" | "sendrecv" => {
-                    return Err(SdpParserInternalError::Generic(format!(
-                        "{} attribute is not allowed to have a value",
-                        name
-                    )));
-                }
-                _ => (),
+        parse_attribute_value(line, &SdpParserConfig::default())
+    }
+}
+
+// Attributes with a fixed (maximum) number of value tokens. All other
+// attributes with values are variadic: candidate, extmap, fmtp, group,
+// identity, ice-options, imageattr, msid-semantic, remote-candidates, rid,
+// rtcp-fb, simulcast, ssrc and ssrc-group.
+fn max_value_tokens(name: &str) -> Option<usize> {
+    match name {
+        "framerate" | "ice-pacing" | "ice-pwd" | "ice-ufrag" | "label" | "max-message-size"
+        | "maxprate" | "maxptime" | "mid" | "ptime" | "sctp-port" | "setup" | "ts-refclk" => {
+            Some(1)
+        }
+        "dtls-message" | "fingerprint" | "mediaclk" | "msid" | "rtpmap" => Some(2),
+        "sctpmap" => Some(3),
+        "rtcp" => Some(4),
+        _ => None,
+    }
+}
+
+fn check_value_token_count(name: &str, value: &str) -> Result<(), SdpParserInternalError> {
+    match max_value_tokens(name) {
+        Some(max) if value.split_whitespace().count() > max => {
+            Err(SdpParserInternalError::Invalid(
+                SdpErrorKind::UnexpectedToken,
+                format!(
+                    "{} attribute must not have more than {} value token(s)",
+                    name, max
+                ),
+            ))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn parse_attribute_value(
+    line: &str,
+    config: &SdpParserConfig,
+) -> Result<SdpAttribute, SdpParserInternalError> {
+    parse_attribute_value_with_buffers(line, config, &mut ParseBuffers::default())
+}
+
+// Scratch space of the attribute parser, which SdpAttributeParser keeps
+// between calls. Empty buffers do not allocate.
+#[derive(Default)]
+struct ParseBuffers {
+    name: String,
+    token_offsets: Vec<(usize, usize)>,
+}
+
+fn parse_attribute_value_with_buffers(
+    line: &str,
+    config: &SdpParserConfig,
+    buffers: &mut ParseBuffers,
+) -> Result<SdpAttribute, SdpParserInternalError> {
+    let (raw_name, val) = match line.find(':') {
+        Some(index) => (&line[..index], Some(line[index + 1..].trim())),
+        None => (line, None),
+    };
+    buffers.name.clear();
+    buffers
+        .name
+        .extend(raw_name.chars().flat_map(char::to_lowercase));
+    let name = buffers.name.as_str();
+    let has_value = val.is_some();
+    let val = val.unwrap_or("");
+    if has_value {
+        match name {
+            "bundle-only" | "end-of-candidates" | "extmap-allow-mixed" | "ice-lite"
+            | "ice-mismatch" | "inactive" | "recvonly" | "rtcp-mux" | "rtcp-rsize" | "sendonly"
+            | "sendrecv" => {
+                return Err(SdpParserInternalError::Invalid(
+                    SdpErrorKind::UnexpectedToken,
+                    format!("{} attribute is not allowed to have a value", name),
+                ));
             }
+            _ => (),
         }
-        match name.as_str() {
-            "bundle-only" => Ok(SdpAttribute::BundleOnly),
-            "dtls-message" => parse_dtls_message(val),
-            "end-of-candidates" => Ok(SdpAttribute::EndOfCandidates),
-            "ice-lite" => Ok(SdpAttribute::IceLite),
-            "ice-mismatch" => Ok(SdpAttribute::IceMismatch),
-            "extmap-allow-mixed" => Ok(SdpAttribute::ExtmapAllowMixed),
-            "ice-pwd" => Ok(SdpAttribute::IcePwd(string_or_empty(val)?)),
-            "ice-ufrag" => Ok(SdpAttribute::IceUfrag(string_or_empty(val)?)),
-            "identity" => Ok(SdpAttribute::Identity(string_or_empty(val)?)),
-            "imageattr" => parse_image_attr(val),
-            "inactive" => Ok(SdpAttribute::Inactive),
-            "label" => Ok(SdpAttribute::Label(string_or_empty(val)?)),
-            "max-message-size" => Ok(SdpAttribute::MaxMessageSize(val.parse()?)),
-            "maxptime" => Ok(SdpAttribute::MaxPtime(val.parse()?)),
-            "mid" => Ok(SdpAttribute::Mid(string_or_empty(val)?)),
-            "msid-semantic" => parse_msid_semantic(val),
-            "ptime" => Ok(SdpAttribute::Ptime(val.parse()?)),
-            "ice-pacing" => parse_ice_pacing(val),
-            "rid" => parse_rid(val),
-            "recvonly" => Ok(SdpAttribute::Recvonly),
-            "rtcp-mux" => Ok(SdpAttribute::RtcpMux),
-            "rtcp-rsize" => Ok(SdpAttribute::RtcpRsize),
-            "sendonly" => Ok(SdpAttribute::Sendonly),
-            "sendrecv" => Ok(SdpAttribute::Sendrecv),
-            "ssrc-group" => parse_ssrc_group(val),
-            "sctp-port" => parse_sctp_port(val),
-            "candidate" => parse_candidate(val),
-            "extmap" => parse_extmap(val),
-            "fingerprint" => parse_fingerprint(val),
-            "fmtp" => parse_fmtp(val),
-            "group" => parse_group(val),
-            "ice-options" => parse_ice_options(val),
-            "msid" => parse_msid(val),
-            "remote-candidates" => parse_remote_candidates(val),
-            "rtpmap" => parse_rtpmap(val),
-            "rtcp" => parse_rtcp(val),
-            "rtcp-fb" => parse_rtcp_fb(val),
-            "sctpmap" => parse_sctpmap(val),
-            "setup" => parse_setup(val),
-            "simulcast" => parse_simulcast(val),
-            "ssrc" => parse_ssrc(val),
-            _ => Err(SdpParserInternalError::Unsupported(format!(
-                "Unknown attribute type {}",
-                name
-            ))),
+        if config.strict {
+            check_value_token_count(name, val)?;
         }
     }
+    match name {
+        "bundle-only" => Ok(SdpAttribute::BundleOnly),
+        "dtls-message" => parse_dtls_message(val),
+        "end-of-candidates" => Ok(SdpAttribute::EndOfCandidates),
+        "ice-lite" => Ok(SdpAttribute::IceLite),
+        "ice-mismatch" => Ok(SdpAttribute::IceMismatch),
+        "extmap-allow-mixed" => Ok(SdpAttribute::ExtmapAllowMixed),
+        "ice-pwd" => Ok(SdpAttribute::IcePwd(parse_ice_credential(
+            "ice-pwd", val, 22, config,
+        )?)),
+        "ice-ufrag" => Ok(SdpAttribute::IceUfrag(parse_ice_credential(
+            "ice-ufrag",
+            val,
+            4,
+            config,
+        )?)),
+        "identity" => Ok(SdpAttribute::Identity(string_or_empty(val)?)),
+        "imageattr" => parse_image_attr(val),
+        "inactive" => Ok(SdpAttribute::Inactive),
+        "label" => Ok(SdpAttribute::Label(string_or_empty(val)?)),
+        "framerate" => Ok(SdpAttribute::Framerate(parse_rate("framerate", val)?)),
+        "max-message-size" => Ok(SdpAttribute::MaxMessageSize(val.parse()?)),
+        "maxprate" => Ok(SdpAttribute::MaxPrate(parse_rate("maxprate", val)?)),
+        "maxptime" => Ok(SdpAttribute::MaxPtime(val.parse()?)),
+        "mediaclk" => parse_mediaclk(val),
+        "mid" => Ok(SdpAttribute::Mid(string_or_empty(val)?)),
+        "msid-semantic" => parse_msid_semantic(val),
+        "ptime" => Ok(SdpAttribute::Ptime(val.parse()?)),
+        "ice-pacing" => parse_ice_pacing(val),
+        "rid" => parse_rid(val),
+        "recvonly" => Ok(SdpAttribute::Recvonly),
+        "rtcp-mux" => Ok(SdpAttribute::RtcpMux),
+        "rtcp-rsize" => Ok(SdpAttribute::RtcpRsize),
+        "sendonly" => Ok(SdpAttribute::Sendonly),
+        "sendrecv" => Ok(SdpAttribute::Sendrecv),
+        "ssrc-group" => parse_ssrc_group(val),
+        "sctp-port" => parse_sctp_port(val),
+        "candidate" => parse_candidate(val, config, &mut buffers.token_offsets),
+        "extmap" => parse_extmap(val),
+        "fingerprint" => parse_fingerprint(val),
+        "fmtp" => parse_fmtp(val),
+        "group" => parse_group(val),
+        "ice-options" => parse_ice_options(val),
+        "msid" => parse_msid(val),
+        "remote-candidates" => parse_remote_candidates(val),
+        "rtpmap" => parse_rtpmap(val, config),
+        "rtcp" => parse_rtcp(val, config),
+        "rtcp-fb" => parse_rtcp_fb(val),
+        "sctpmap" => parse_sctpmap(val),
+        "setup" => parse_setup(val),
+        "simulcast" => parse_simulcast(val),
+        "ssrc" => parse_ssrc(val),
+        "ts-refclk" => parse_ts_refclk(val),
+        _ => Err(SdpParserInternalError::Unsupported(format!(
+            "Unknown attribute type {}",
+            name
+        ))),
+    }
 }
 
 impl fmt::Display for SdpAttribute {
@@ -1414,6 +2209,7 @@ impl fmt::Display for SdpAttribute {
             SdpAttribute::ExtmapAllowMixed => SdpAttributeType::ExtmapAllowMixed.to_string(),
             SdpAttribute::Fingerprint(ref a) => attr_to_string(a.to_string()),
             SdpAttribute::Fmtp(ref a) => attr_to_string(a.to_string()),
+            SdpAttribute::Framerate(ref a) => attr_to_string(a.to_string()),
             SdpAttribute::Group(ref a) => attr_to_string(a.to_string()),
             SdpAttribute::IceLite => SdpAttributeType::IceLite.to_string(),
             SdpAttribute::IceMismatch => SdpAttributeType::IceMismatch.to_string(),
@@ -1426,7 +2222,9 @@ impl fmt::Display for SdpAttribute {
             SdpAttribute::Inactive => SdpAttributeType::Inactive.to_string(),
             SdpAttribute::Label(ref a) => attr_to_string(a.to_string()),
             SdpAttribute::MaxMessageSize(ref a) => attr_to_string(a.to_string()),
+            SdpAttribute::MaxPrate(ref a) => attr_to_string(a.to_string()),
             SdpAttribute::MaxPtime(ref a) => attr_to_string(a.to_string()),
+            SdpAttribute::MediaClk(ref a) => attr_to_string(a.to_string()),
             SdpAttribute::Mid(ref a) => attr_to_string(a.to_string()),
             SdpAttribute::Msid(ref a) => attr_to_string(a.to_string()),
             SdpAttribute::MsidSemantic(ref a) => attr_to_string(a.to_string()),
@@ -1451,6 +2249,7 @@ impl fmt::Display for SdpAttribute {
                     ssrcs.iter().map(|ssrc| ssrc.to_string()).collect();
                 attr_to_string(a.to_string()) + " " + &stringified_ssrcs.join(" ")
             }
+            SdpAttribute::TsRefclk(ref a) => attr_to_string(a.to_string()),
         }
         .fmt(f)
     }
@@ -1470,7 +2269,9 @@ impl AnonymizingClone for SdpAttribute {
     }
 }
 
-#[derive(Clone, PartialEq)]
+/// The ordering of attribute types follows their declaration order. It has no
+/// meaning beyond giving attributes a deterministic sort order.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum SdpAttributeType {
     BundleOnly,
     Candidate,
@@ -1480,6 +2281,7 @@ pub enum SdpAttributeType {
     ExtmapAllowMixed,
     Fingerprint,
     Fmtp,
+    Framerate,
     Group,
     IceLite,
     IceMismatch,
@@ -1492,7 +2294,9 @@ pub enum SdpAttributeType {
     Inactive,
     Label,
     MaxMessageSize,
+    MaxPrate,
     MaxPtime,
+    MediaClk,
     Mid,
     Msid,
     MsidSemantic,
@@ -1513,6 +2317,7 @@ pub enum SdpAttributeType {
     Simulcast,
     Ssrc,
     SsrcGroup,
+    TsRefclk,
 }
 
 impl<'a> From<&'a SdpAttribute> for SdpAttributeType {
@@ -1526,6 +2331,7 @@ impl<'a> From<&'a SdpAttribute> for SdpAttributeType {
             SdpAttribute::ExtmapAllowMixed { .. } => SdpAttributeType::ExtmapAllowMixed,
             SdpAttribute::Fingerprint { .. } => SdpAttributeType::Fingerprint,
             SdpAttribute::Fmtp { .. } => SdpAttributeType::Fmtp,
+            SdpAttribute::Framerate { .. } => SdpAttributeType::Framerate,
             SdpAttribute::Group { .. } => SdpAttributeType::Group,
             SdpAttribute::IceLite { .. } => SdpAttributeType::IceLite,
             SdpAttribute::IceMismatch { .. } => SdpAttributeType::IceMismatch,
@@ -1538,7 +2344,9 @@ impl<'a> From<&'a SdpAttribute> for SdpAttributeType {
             SdpAttribute::Inactive { .. } => SdpAttributeType::Inactive,
             SdpAttribute::Label { .. } => SdpAttributeType::Label,
             SdpAttribute::MaxMessageSize { .. } => SdpAttributeType::MaxMessageSize,
+            SdpAttribute::MaxPrate { .. } => SdpAttributeType::MaxPrate,
             SdpAttribute::MaxPtime { .. } => SdpAttributeType::MaxPtime,
+            SdpAttribute::MediaClk { .. } => SdpAttributeType::MediaClk,
             SdpAttribute::Mid { .. } => SdpAttributeType::Mid,
             SdpAttribute::Msid { .. } => SdpAttributeType::Msid,
             SdpAttribute::MsidSemantic { .. } => SdpAttributeType::MsidSemantic,
@@ -1559,10 +2367,78 @@ impl<'a> From<&'a SdpAttribute> for SdpAttributeType {
             SdpAttribute::Simulcast { .. } => SdpAttributeType::Simulcast,
             SdpAttribute::Ssrc { .. } => SdpAttributeType::Ssrc,
             SdpAttribute::SsrcGroup { .. } => SdpAttributeType::SsrcGroup,
+            SdpAttribute::TsRefclk { .. } => SdpAttributeType::TsRefclk,
         }
     }
 }
 
+impl SdpAttributeType {
+    /// Whether the attribute may appear more than once within the same
+    /// session or media section.
+    pub fn allows_multiple(&self) -> bool {
+        match *self {
+            SdpAttributeType::Candidate
+            | SdpAttributeType::Extmap
+            | SdpAttributeType::Fingerprint
+            | SdpAttributeType::Fmtp
+            | SdpAttributeType::Group
+            | SdpAttributeType::ImageAttr
+            | SdpAttributeType::Msid
+            | SdpAttributeType::MsidSemantic
+            | SdpAttributeType::Rid
+            | SdpAttributeType::Rtpmap
+            | SdpAttributeType::Rtcpfb
+            | SdpAttributeType::Sctpmap
+            | SdpAttributeType::Ssrc
+            | SdpAttributeType::SsrcGroup
+            | SdpAttributeType::TsRefclk => true,
+
+            SdpAttributeType::BundleOnly
+            | SdpAttributeType::DtlsMessage
+            | SdpAttributeType::EndOfCandidates
+            | SdpAttributeType::ExtmapAllowMixed
+            | SdpAttributeType::Framerate
+            | SdpAttributeType::IceLite
+            | SdpAttributeType::IceMismatch
+            | SdpAttributeType::IceOptions
+            | SdpAttributeType::IcePacing
+            | SdpAttributeType::IcePwd
+            | SdpAttributeType::IceUfrag
+            | SdpAttributeType::Identity
+            | SdpAttributeType::Inactive
+            | SdpAttributeType::Label
+            | SdpAttributeType::MaxMessageSize
+            | SdpAttributeType::MaxPrate
+            | SdpAttributeType::MaxPtime
+            | SdpAttributeType::MediaClk
+            | SdpAttributeType::Mid
+            | SdpAttributeType::Ptime
+            | SdpAttributeType::Recvonly
+            | SdpAttributeType::RemoteCandidate
+            | SdpAttributeType::Rtcp
+            | SdpAttributeType::RtcpMux
+            | SdpAttributeType::RtcpRsize
+            | SdpAttributeType::SctpPort
+            | SdpAttributeType::Sendonly
+            | SdpAttributeType::Sendrecv
+            | SdpAttributeType::Setup
+            | SdpAttributeType::Simulcast => false,
+        }
+    }
+
+    /// Whether JSEP considers the attribute deprecated. Such attributes are
+    /// still parsed, but new offers should not rely on them.
+    /// parse_attribute_with_warnings reports them as Deprecated warnings.
+    pub fn is_deprecated(&self) -> bool {
+        matches!(
+            *self,
+            SdpAttributeType::MsidSemantic
+                | SdpAttributeType::Sctpmap
+                | SdpAttributeType::SsrcGroup
+        )
+    }
+}
+
 impl fmt::Display for SdpAttributeType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
@@ -1574,6 +2450,7 @@ impl fmt::Display for SdpAttributeType {
             SdpAttributeType::ExtmapAllowMixed => "extmap-allow-mixed",
             SdpAttributeType::Fingerprint => "fingerprint",
             SdpAttributeType::Fmtp => "fmtp",
+            SdpAttributeType::Framerate => "framerate",
             SdpAttributeType::Group => "group",
             SdpAttributeType::IceLite => "ice-lite",
             SdpAttributeType::IceMismatch => "ice-mismatch",
@@ -1586,7 +2463,9 @@ impl fmt::Display for SdpAttributeType {
             SdpAttributeType::Inactive => "inactive",
             SdpAttributeType::Label => "label",
             SdpAttributeType::MaxMessageSize => "max-message-size",
+            SdpAttributeType::MaxPrate => "maxprate",
             SdpAttributeType::MaxPtime => "maxptime",
+            SdpAttributeType::MediaClk => "mediaclk",
             SdpAttributeType::Mid => "mid",
             SdpAttributeType::Msid => "msid",
             SdpAttributeType::MsidSemantic => "msid-semantic",
@@ -1607,14 +2486,74 @@ impl fmt::Display for SdpAttributeType {
             SdpAttributeType::Simulcast => "simulcast",
             SdpAttributeType::Ssrc => "ssrc",
             SdpAttributeType::SsrcGroup => "ssrc-group",
+            SdpAttributeType::TsRefclk => "ts-refclk",
         }
         .fmt(f)
     }
 }
 
+impl FromStr for SdpAttributeType {
+    type Err = SdpParserInternalError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "bundle-only" => Ok(SdpAttributeType::BundleOnly),
+            "candidate" => Ok(SdpAttributeType::Candidate),
+            "dtls-message" => Ok(SdpAttributeType::DtlsMessage),
+            "end-of-candidates" => Ok(SdpAttributeType::EndOfCandidates),
+            "extmap" => Ok(SdpAttributeType::Extmap),
+            "extmap-allow-mixed" => Ok(SdpAttributeType::ExtmapAllowMixed),
+            "fingerprint" => Ok(SdpAttributeType::Fingerprint),
+            "fmtp" => Ok(SdpAttributeType::Fmtp),
+            "framerate" => Ok(SdpAttributeType::Framerate),
+            "group" => Ok(SdpAttributeType::Group),
+            "ice-lite" => Ok(SdpAttributeType::IceLite),
+            "ice-mismatch" => Ok(SdpAttributeType::IceMismatch),
+            "ice-options" => Ok(SdpAttributeType::IceOptions),
+            "ice-pacing" => Ok(SdpAttributeType::IcePacing),
+            "ice-pwd" => Ok(SdpAttributeType::IcePwd),
+            "ice-ufrag" => Ok(SdpAttributeType::IceUfrag),
+            "identity" => Ok(SdpAttributeType::Identity),
+            "imageattr" => Ok(SdpAttributeType::ImageAttr),
+            "inactive" => Ok(SdpAttributeType::Inactive),
+            "label" => Ok(SdpAttributeType::Label),
+            "max-message-size" => Ok(SdpAttributeType::MaxMessageSize),
+            "maxprate" => Ok(SdpAttributeType::MaxPrate),
+            "maxptime" => Ok(SdpAttributeType::MaxPtime),
+            "mediaclk" => Ok(SdpAttributeType::MediaClk),
+            "mid" => Ok(SdpAttributeType::Mid),
+            "msid" => Ok(SdpAttributeType::Msid),
+            "msid-semantic" => Ok(SdpAttributeType::MsidSemantic),
+            "ptime" => Ok(SdpAttributeType::Ptime),
+            "rid" => Ok(SdpAttributeType::Rid),
+            "recvonly" => Ok(SdpAttributeType::Recvonly),
+            "remote-candidates" => Ok(SdpAttributeType::RemoteCandidate),
+            "rtpmap" => Ok(SdpAttributeType::Rtpmap),
+            "rtcp" => Ok(SdpAttributeType::Rtcp),
+            "rtcp-fb" => Ok(SdpAttributeType::Rtcpfb),
+            "rtcp-mux" => Ok(SdpAttributeType::RtcpMux),
+            "rtcp-rsize" => Ok(SdpAttributeType::RtcpRsize),
+            "sctpmap" => Ok(SdpAttributeType::Sctpmap),
+            "sctp-port" => Ok(SdpAttributeType::SctpPort),
+            "sendonly" => Ok(SdpAttributeType::Sendonly),
+            "sendrecv" => Ok(SdpAttributeType::Sendrecv),
+            "setup" => Ok(SdpAttributeType::Setup),
+            "simulcast" => Ok(SdpAttributeType::Simulcast),
+            "ssrc" => Ok(SdpAttributeType::Ssrc),
+            "ssrc-group" => Ok(SdpAttributeType::SsrcGroup),
+            "ts-refclk" => Ok(SdpAttributeType::TsRefclk),
+            _ => Err(SdpParserInternalError::Unsupported(format!(
+                "Unknown attribute type {}",
+                s
+            ))),
+        }
+    }
+}
+
 fn string_or_empty(to_parse: &str) -> Result<String, SdpParserInternalError> {
     if to_parse.is_empty() {
-        Err(SdpParserInternalError::Generic(
+        Err(SdpParserInternalError::Invalid(
+            SdpErrorKind::MissingToken,
             "This attribute is required to have a value".to_string(),
         ))
     } else {
@@ -1622,6 +2561,67 @@ fn string_or_empty(to_parse: &str) -> Result<String, SdpParserInternalError> {
     }
 }
 
+// ice-char = ALPHA / DIGIT / "+" / "/"
+fn is_ice_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '+' || c == '/'
+}
+
+///////////////////////////////////////////////////////////////////////////
+// a=ice-ufrag and a=ice-pwd, RFC8839
+//-------------------------------------------------------------------------
+//   ice-pwd-att            = "ice-pwd:" password
+//   ice-ufrag-att          = "ice-ufrag:" ufrag
+//   password               = 22*256ice-char
+//   ufrag                  = 4*256ice-char
+//
+// Whitespace is never valid, but the ice-char class and the length limits
+// are only enforced in strict mode, as implementations (and our own
+// anonymizer with its '-' separated names) use credentials which do not
+// follow them. So the default mode accepts credentials like "ab-c".
+//
+// The credentials are secrets, so the error messages never include them.
+fn parse_ice_credential(
+    name: &str,
+    to_parse: &str,
+    min_len: usize,
+    config: &SdpParserConfig,
+) -> Result<String, SdpParserInternalError> {
+    let value = string_or_empty(to_parse)?;
+    if value.contains(char::is_whitespace) {
+        return Err(SdpParserInternalError::Invalid(
+            SdpErrorKind::InvalidValue,
+            format!("{} must not contain whitespace", name),
+        ));
+    }
+    if config.strict
+        && (value.len() < min_len || value.len() > 256 || !value.chars().all(is_ice_char))
+    {
+        return Err(SdpParserInternalError::Invalid(
+            SdpErrorKind::InvalidValue,
+            format!("{} must consist of {} to 256 ice-chars", name, min_len),
+        ));
+    }
+    Ok(value)
+}
+
+///////////////////////////////////////////////////////////////////////////
+// a=framerate, RFC4566 and a=maxprate, RFC3890
+//-------------------------------------------------------------------------
+// framerate-value = integer ["." integer]
+// maxprate-value  = integer ["." integer]
+//
+// Both complement the framerate constraints negotiated through imageattr.
+fn parse_rate(name: &str, to_parse: &str) -> Result<f64, SdpParserInternalError> {
+    let rate = to_parse.parse::<f64>()?;
+    if !rate.is_finite() || rate <= 0.0 {
+        return Err(SdpParserInternalError::Invalid(
+            SdpErrorKind::InvalidNumber,
+            format!("{} must be a positive number", name),
+        ));
+    }
+    Ok(rate)
+}
+
 fn parse_payload_type(to_parse: &str) -> Result<SdpAttributePayloadType, SdpParserInternalError> {
     Ok(match to_parse {
         "*" => SdpAttributePayloadType::Wildcard,
@@ -1633,10 +2633,10 @@ fn parse_single_direction(to_parse: &str) -> Result<SdpSingleDirection, SdpParse
     match to_parse {
         "send" => Ok(SdpSingleDirection::Send),
         "recv" => Ok(SdpSingleDirection::Recv),
-        x => Err(SdpParserInternalError::Generic(format!(
-            "Unknown direction description found: '{:}'",
-            x
-        ))),
+        x => Err(SdpParserInternalError::Invalid(
+            SdpErrorKind::InvalidValue,
+            format!("Unknown direction description found: '{:}'", x),
+        )),
     }
 }
 
@@ -1648,7 +2648,8 @@ fn parse_ssrc_group(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalErr
     let mut tokens = to_parse.split_whitespace();
     let semantics = match tokens.next() {
         None => {
-            return Err(SdpParserInternalError::Generic(
+            return Err(SdpParserInternalError::Invalid(
+                SdpErrorKind::MissingToken,
                 "Ssrc group attribute is missing semantics".to_string(),
             ));
         }
@@ -1673,35 +2674,221 @@ fn parse_ssrc_group(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalErr
             Ok(SdpAttribute::Ssrc(ssrc)) => {
                 ssrcs.push(ssrc);
             }
-            Err(err) => {
-                return Err(err);
+            Err(err) => {
+                return Err(err);
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    if ssrcs.is_empty() {
+        return Err(SdpParserInternalError::Invalid(
+            SdpErrorKind::MissingToken,
+            "Ssrc group must contain at least one ssrc".to_string(),
+        ));
+    }
+
+    Ok(SdpAttribute::SsrcGroup(semantics, ssrcs))
+}
+
+///////////////////////////////////////////////////////////////////////////
+// a=sctp-port, draft-ietf-mmusic-sctp-sdp-26#section-15.2.1
+//-------------------------------------------------------------------------
+// no ABNF given
+fn parse_sctp_port(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
+    let port = to_parse.parse()?;
+    if port > 65535 {
+        return Err(SdpParserInternalError::Invalid(
+            SdpErrorKind::PortOutOfRange,
+            format!("Sctpport port {} can only be a bit 16bit number", port),
+        ));
+    }
+    Ok(SdpAttribute::SctpPort(port))
+}
+
+///////////////////////////////////////////////////////////////////////////
+// a=ts-refclk, RFC7273
+//-------------------------------------------------------------------------
+// ts-refclk = "ts-refclk:" clksrc
+// clksrc    = ntp / ptp / gps / gal / glonass / local / private
+// ntp       = "ntp=" ntp-server-addr / "ntp=/traceable/"
+// ptp       = "ptp=" ptp-version ":" ptp-gmid [":" ptp-domain]
+//             / "ptp=" ptp-version ":traceable"
+// local     = "local"
+fn parse_ts_refclk(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
+    let (source, value) = match to_parse.find('=') {
+        Some(index) => (&to_parse[..index], Some(&to_parse[index + 1..])),
+        None => (to_parse, None),
+    };
+    let refclk = match (source.to_lowercase().as_ref(), value) {
+        ("ntp", Some(server)) if !server.is_empty() => {
+            SdpAttributeTsRefclk::Ntp(server.to_string())
+        }
+        ("ptp", Some(ptp)) => {
+            let tokens: Vec<&str> = ptp.split(':').collect();
+            if tokens.len() < 2 || tokens.len() > 3 || tokens[0].is_empty() {
+                return Err(SdpParserInternalError::Invalid(
+                    SdpErrorKind::MissingToken,
+                    "ts-refclk ptp needs a version and a grandmaster id".to_string(),
+                ));
+            }
+            let grandmaster = match tokens[1] {
+                "traceable" if tokens.len() == 2 => None,
+                gmid if !gmid.is_empty() && gmid != "traceable" => Some(gmid.to_string()),
+                _ => {
+                    return Err(SdpParserInternalError::Invalid(
+                        SdpErrorKind::InvalidValue,
+                        "ts-refclk ptp has an invalid grandmaster id".to_string(),
+                    ));
+                }
+            };
+            let domain = match tokens.get(2) {
+                Some(domain) => {
+                    let domain = domain.parse::<u8>()?;
+                    if domain > 127 {
+                        return Err(SdpParserInternalError::Invalid(
+                            SdpErrorKind::InvalidNumber,
+                            "ts-refclk ptp domain must be less than 128".to_string(),
+                        ));
+                    }
+                    Some(domain)
+                }
+                None => None,
+            };
+            SdpAttributeTsRefclk::Ptp {
+                version: tokens[0].to_string(),
+                grandmaster,
+                domain,
+            }
+        }
+        ("local", None) => SdpAttributeTsRefclk::Local,
+        ("ntp", _) | ("ptp", _) | ("local", _) => {
+            return Err(SdpParserInternalError::Invalid(
+                SdpErrorKind::InvalidValue,
+                format!("Invalid ts-refclk value '{}'", to_parse),
+            ));
+        }
+        _ => {
+            return Err(SdpParserInternalError::Unsupported(format!(
+                "Unsupported ts-refclk clock source '{}'",
+                source
+            )));
+        }
+    };
+    Ok(SdpAttribute::TsRefclk(refclk))
+}
+
+///////////////////////////////////////////////////////////////////////////
+// a=mediaclk, RFC7273
+//-------------------------------------------------------------------------
+// mediaclk = "mediaclk:" [media-clock-id SP] mediaclock-source
+//            [SP rate]
+// mediaclock-source = "sender" / direct / IEEE1722
+// direct   = "direct" [ "=" 1*DIGIT ]
+// rate     = "rate=" integer / integer
+fn parse_mediaclk(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
+    let mut tokens = to_parse.split_whitespace();
+    let source = match tokens.next() {
+        Some(source) => source,
+        None => {
+            return Err(SdpParserInternalError::Invalid(
+                SdpErrorKind::MissingToken,
+                "mediaclk requires a clock source".to_string(),
+            ));
+        }
+    };
+    let clock = match source.to_lowercase().as_ref() {
+        "sender" => SdpAttributeMediaClk::Sender,
+        "direct" => SdpAttributeMediaClk::Direct {
+            offset: None,
+            rate: None,
+        },
+        direct if direct.starts_with("direct=") => SdpAttributeMediaClk::Direct {
+            offset: Some(direct["direct=".len()..].parse::<u64>()?),
+            rate: None,
+        },
+        _ => {
+            return Err(SdpParserInternalError::Unsupported(format!(
+                "Unsupported mediaclk clock source '{}'",
+                source
+            )));
+        }
+    };
+    let clock = match (clock, tokens.next()) {
+        (clock, None) => clock,
+        (SdpAttributeMediaClk::Direct { offset, .. }, Some(rate)) if rate.starts_with("rate=") => {
+            let mut parts = rate["rate=".len()..].split('/');
+            let numerator = parts.next().unwrap_or("").parse::<u32>()?;
+            let denominator = match parts.next() {
+                Some(denominator) => denominator.parse::<u32>()?,
+                None => 1,
+            };
+            if parts.next().is_some() || denominator == 0 {
+                return Err(SdpParserInternalError::Invalid(
+                    SdpErrorKind::InvalidNumber,
+                    format!("Invalid mediaclk rate '{}'", rate),
+                ));
+            }
+            SdpAttributeMediaClk::Direct {
+                offset,
+                rate: Some((numerator, denominator)),
             }
-            _ => unreachable!(),
         }
+        (_, Some(token)) => {
+            return Err(SdpParserInternalError::Invalid(
+                SdpErrorKind::UnexpectedToken,
+                format!("Unexpected mediaclk token '{}'", token),
+            ));
+        }
+    };
+    if let Some(token) = tokens.next() {
+        return Err(SdpParserInternalError::Invalid(
+            SdpErrorKind::UnexpectedToken,
+            format!("Unexpected mediaclk token '{}'", token),
+        ));
     }
+    Ok(SdpAttribute::MediaClk(clock))
+}
 
-    if ssrcs.is_empty() {
-        return Err(SdpParserInternalError::Generic(
-            "Ssrc group must contain at least one ssrc".to_string(),
-        ));
+// Whitespace separated tokens of a line, stored as byte offsets into the
+// line so that the offsets vector can be reused between lines.
+struct Tokens<'a> {
+    line: &'a str,
+    offsets: &'a [(usize, usize)],
+}
+
+impl<'a> Tokens<'a> {
+    fn split_whitespace(line: &'a str, offsets: &'a mut Vec<(usize, usize)>) -> Self {
+        offsets.clear();
+        let mut start = None;
+        for (index, c) in line.char_indices() {
+            match (c.is_whitespace(), start) {
+                (true, Some(begin)) => {
+                    offsets.push((begin, index));
+                    start = None;
+                }
+                (false, None) => start = Some(index),
+                _ => (),
+            }
+        }
+        if let Some(begin) = start {
+            offsets.push((begin, line.len()));
+        }
+        Tokens { line, offsets }
     }
 
-    Ok(SdpAttribute::SsrcGroup(semantics, ssrcs))
+    fn len(&self) -> usize {
+        self.offsets.len()
+    }
 }
 
-///////////////////////////////////////////////////////////////////////////
-// a=sctp-port, draft-ietf-mmusic-sctp-sdp-26#section-15.2.1
-//-------------------------------------------------------------------------
-// no ABNF given
-fn parse_sctp_port(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
-    let port = to_parse.parse()?;
-    if port > 65535 {
-        return Err(SdpParserInternalError::Generic(format!(
-            "Sctpport port {} can only be a bit 16bit number",
-            port
-        )));
+impl<'a> std::ops::Index<usize> for Tokens<'a> {
+    type Output = str;
+
+    fn index(&self, index: usize) -> &str {
+        let (begin, end) = self.offsets[index];
+        &self.line[begin..end]
     }
-    Ok(SdpAttribute::SctpPort(port))
 }
 
 ///////////////////////////////////////////////////////////////////////////
@@ -1730,52 +2917,114 @@ fn parse_sctp_port(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalErro
 // extension-att-name    = byte-string    ;from RFC 4566
 // extension-att-value   = byte-string
 // ice-char              = ALPHA / DIGIT / "+" / "/"
-fn parse_candidate(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
-    let tokens: Vec<&str> = to_parse.split_whitespace().collect();
+fn parse_candidate(
+    to_parse: &str,
+    config: &SdpParserConfig,
+    token_offsets: &mut Vec<(usize, usize)>,
+) -> Result<SdpAttribute, SdpParserInternalError> {
+    let tokens = Tokens::split_whitespace(to_parse, token_offsets);
     if tokens.len() < 8 {
-        return Err(SdpParserInternalError::Generic(
+        return Err(SdpParserInternalError::Invalid(
+            SdpErrorKind::MissingToken,
             "Candidate needs to have minimum eigth tokens".to_string(),
         ));
     }
-    let component = tokens[1].parse::<u32>()?;
-    let transport = match tokens[2].to_lowercase().as_ref() {
-        "udp" => SdpAttributeCandidateTransport::Udp,
-        "tcp" => SdpAttributeCandidateTransport::Tcp,
-        _ => {
-            return Err(SdpParserInternalError::Generic(
-                "Unknonw candidate transport value".to_string(),
+    // Splitting on whitespace never yields an empty foundation
+    let foundation = &tokens[0];
+    if foundation.len() > 32 || !foundation.chars().all(is_ice_char) {
+        return Err(SdpParserInternalError::Invalid(
+            SdpErrorKind::InvalidValue,
+            format!(
+                "ICE candidate foundation '{}' must consist of 1 to 32 ice-chars",
+                foundation
+            ),
+        ));
+    }
+    let invalid_number = |field: &str, token: &str| {
+        SdpParserInternalError::Invalid(
+            SdpErrorKind::InvalidNumber,
+            format!(
+                "ICE candidate {} must be a non-negative integer, found '{}'",
+                field, token
+            ),
+        )
+    };
+    let component = tokens[1]
+        .parse::<u32>()
+        .map_err(|_| invalid_number("component", &tokens[1]))?;
+    let transport = match SdpAttributeCandidateTransport::from_str(&tokens[2]) {
+        Err(_) if !config.strict && !tokens[2].contains('/') => {
+            SdpAttributeCandidateTransport::Unknown(tokens[2].to_string())
+        }
+        transport => transport?,
+    };
+    let priority = tokens[3]
+        .parse::<u64>()
+        .map_err(|_| invalid_number("priority", &tokens[3]))?;
+    // RFC 8445 defines the priority as a 32bit value, even though the grammar
+    // allows up to ten digits.
+    if priority > u64::from(u32::MAX) {
+        return Err(SdpParserInternalError::Invalid(
+            SdpErrorKind::InvalidNumber,
+            "ICE candidate priority can only be a 32bit number".to_string(),
+        ));
+    }
+    let priority = priority as u32;
+    // Some encoders wrap IPv6 addresses in brackets, which candidates do not
+    // use. This is only tolerated outside of strict mode.
+    let address_token = match tokens[4]
+        .strip_prefix('[')
+        .and_then(|token| token.strip_suffix(']'))
+    {
+        Some(unbracketed) if !config.strict && unbracketed.contains(':') => unbracketed,
+        Some(_) => {
+            return Err(SdpParserInternalError::Invalid(
+                SdpErrorKind::InvalidAddress,
+                format!(
+                    "ICE candidate address '{}' must not be enclosed in brackets",
+                    &tokens[4]
+                ),
             ));
         }
+        None => &tokens[4],
+    };
+    let (address, scope) = match address_token.find('%') {
+        Some(index) => match Address::from_str(&address_token[..index])? {
+            address @ Address::Ip(IpAddr::V6(_)) if index + 1 < address_token.len() => {
+                (address, Some(address_token[index + 1..].to_string()))
+            }
+            _ => {
+                return Err(SdpParserInternalError::Invalid(
+                    SdpErrorKind::InvalidAddress,
+                    "ICE candidate zone ids are only allowed on IPv6 addresses".to_string(),
+                ));
+            }
+        },
+        None => (Address::from_str(address_token)?, None),
     };
-    let priority = tokens[3].parse::<u64>()?;
-    let address = Address::from_str(tokens[4])?;
     let port = tokens[5].parse::<u32>()?;
     if port > 65535 {
-        return Err(SdpParserInternalError::Generic(
+        return Err(SdpParserInternalError::Invalid(
+            SdpErrorKind::PortOutOfRange,
             "ICE candidate port can only be a bit 16bit number".to_string(),
         ));
     }
     match tokens[6].to_lowercase().as_ref() {
         "typ" => (),
         _ => {
-            return Err(SdpParserInternalError::Generic(
+            return Err(SdpParserInternalError::Invalid(
+                SdpErrorKind::UnexpectedToken,
                 "Candidate attribute token must be 'typ'".to_string(),
             ));
         }
     };
-    let cand_type = match tokens[7].to_lowercase().as_ref() {
-        "host" => SdpAttributeCandidateType::Host,
-        "srflx" => SdpAttributeCandidateType::Srflx,
-        "prflx" => SdpAttributeCandidateType::Prflx,
-        "relay" => SdpAttributeCandidateType::Relay,
-        _ => {
-            return Err(SdpParserInternalError::Generic(
-                "Unknow candidate type value".to_string(),
-            ));
-        }
+    let cand_type = SdpAttributeCandidateType::from_str(&tokens[7])?;
+    let resolved_address = match (&address, config.hostname_resolver) {
+        (Address::Fqdn(name), Some(resolve)) => resolve(name),
+        _ => None,
     };
     let mut cand = SdpAttributeCandidate::new(
-        tokens[0].to_string(),
+        foundation.to_string(),
         component,
         transport,
         priority,
@@ -1783,6 +3032,10 @@ fn parse_candidate(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalErro
         port,
         cand_type,
     );
+    if let Some(ip) = resolved_address {
+        cand.set_resolved_address(ip);
+    }
+    cand.scope = scope;
     if tokens.len() > 8 {
         let mut index = 8;
         while tokens.len() > index + 1 {
@@ -1793,19 +3046,25 @@ fn parse_candidate(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalErro
                     index += 2;
                 }
                 "network-cost" => {
-                    let cost = tokens[index + 1].parse::<u32>()?;
+                    let cost = tokens[index + 1].parse::<u16>()?;
                     cand.set_network_cost(cost);
                     index += 2;
                 }
+                "network-id" => {
+                    let id = tokens[index + 1].parse::<u16>()?;
+                    cand.set_network_id(id);
+                    index += 2;
+                }
                 "raddr" => {
-                    let addr = parse_unicast_address(tokens[index + 1])?;
+                    let addr = Address::from_str(&tokens[index + 1])?;
                     cand.set_remote_address(addr);
                     index += 2;
                 }
                 "rport" => {
                     let port = tokens[index + 1].parse::<u32>()?;
                     if port > 65535 {
-                        return Err(SdpParserInternalError::Generic(
+                        return Err(SdpParserInternalError::Invalid(
+                            SdpErrorKind::PortOutOfRange,
                             "ICE candidate rport can only be a bit 16bit number".to_string(),
                         ));
                     }
@@ -1813,24 +3072,22 @@ fn parse_candidate(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalErro
                     index += 2;
                 }
                 "tcptype" => {
-                    cand.set_tcp_type(match tokens[index + 1].to_lowercase().as_ref() {
-                        "active" => SdpAttributeCandidateTcpType::Active,
-                        "passive" => SdpAttributeCandidateTcpType::Passive,
-                        "so" => SdpAttributeCandidateTcpType::Simultaneous,
-                        _ => {
-                            return Err(SdpParserInternalError::Generic(
-                                "Unknown tcptype value in candidate line".to_string(),
-                            ));
-                        }
-                    });
+                    cand.set_tcp_type(SdpAttributeCandidateTcpType::from_str(&tokens[index + 1])?);
                     index += 2;
                 }
                 "ufrag" => {
-                    let ufrag = tokens[index + 1];
+                    let ufrag = &tokens[index + 1];
                     cand.set_ufrag(ufrag.to_string());
                     index += 2;
                 }
                 _ => {
+                    if config.strict {
+                        return Err(SdpParserInternalError::Unsupported(format!(
+                            "Unknown ICE candidate extension '{}'",
+                            &tokens[index]
+                        )));
+                    }
+                    warn!("Keeping unknown ICE candidate extension {}", &tokens[index]);
                     let name = tokens[index].to_string();
                     let value = tokens[index + 1].to_string();
                     cand.add_unknown_extension(name, value);
@@ -1839,14 +3096,83 @@ fn parse_candidate(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalErro
             };
         }
         if tokens.len() > index {
-            return Err(SdpParserInternalError::Unsupported(
-                "Ice candidate extension name without value".to_string(),
+            return Err(SdpParserInternalError::Invalid(
+                SdpErrorKind::MissingToken,
+                format!(
+                    "Ice candidate extension {} is missing its value",
+                    &tokens[index]
+                ),
             ));
         }
     }
+    check_candidate_related_address(&cand)?;
     Ok(SdpAttribute::Candidate(cand))
 }
 
+// Host candidates have no related address, while the address of all other
+// candidate types is derived from one (RFC 8839 section 5.1).
+fn check_candidate_related_address(
+    cand: &SdpAttributeCandidate,
+) -> Result<(), SdpParserInternalError> {
+    let missing = match (&cand.c_type, &cand.raddr, cand.rport) {
+        (SdpAttributeCandidateType::Host, None, None) => return Ok(()),
+        (SdpAttributeCandidateType::Host, _, _) => {
+            return Err(SdpParserInternalError::Invalid(
+                SdpErrorKind::UnexpectedToken,
+                "ICE host candidates must not have a raddr or rport".to_string(),
+            ));
+        }
+        (_, None, _) => "raddr",
+        (_, _, None) => "rport",
+        (_, Some(_), Some(_)) => return Ok(()),
+    };
+    Err(SdpParserInternalError::Invalid(
+        SdpErrorKind::MissingToken,
+        format!("ICE {} candidates require a {}", cand.c_type, missing),
+    ))
+}
+
+/// Computes the priority of a candidate as defined in RFC8445 section 5.1.2.1.
+/// Component ids start at 1, so None is returned for component 0.
+pub fn compute_candidate_priority(type_pref: u8, local_pref: u16, component: u8) -> Option<u32> {
+    if component == 0 {
+        return None;
+    }
+    Some(
+        (1 << 24) * u32::from(type_pref)
+            + (1 << 8) * u32::from(local_pref)
+            + (256 - u32::from(component)),
+    )
+}
+
+/// Splits a candidate priority into the type preference, local preference
+/// and component id it was computed from. Returns None if the priority can
+/// not be the result of compute_candidate_priority.
+pub fn priority_components(priority: u32) -> Option<(u8, u16, u8)> {
+    let component = 256 - (priority & 0xff);
+    if component > 255 {
+        return None;
+    }
+    Some((
+        (priority >> 24) as u8,
+        ((priority >> 8) & 0xffff) as u16,
+        component as u8,
+    ))
+}
+
+/// Drops the RTCP (component 2) candidates when rtcp-mux is in use, as RTCP
+/// then shares the RTP candidates.
+pub fn filter_muxed_candidates(
+    candidates: &[SdpAttributeCandidate],
+    rtcp_mux: bool,
+) -> Vec<SdpAttributeCandidate> {
+    candidates
+        .iter()
+        .filter(|candidate| !rtcp_mux || candidate.component != 2)
+        .cloned()
+        .collect()
+}
+
 ///////////////////////////////////////////////////////////////////////////
 // a=dtls-message, draft-rescorla-dtls-in-sdp
 //-------------------------------------------------------------------------
@@ -1862,7 +3188,8 @@ fn parse_dtls_message(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalE
     let tokens: Vec<&str> = to_parse.split(' ').collect();
 
     if tokens.len() != 2 {
-        return Err(SdpParserInternalError::Generic(
+        return Err(SdpParserInternalError::Invalid(
+            SdpErrorKind::TokenCount,
             "dtls-message must have a role token and a value token.".to_string(),
         ));
     }
@@ -1871,10 +3198,10 @@ fn parse_dtls_message(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalE
         "client" => SdpAttributeDtlsMessage::Client(tokens[1].to_string()),
         "server" => SdpAttributeDtlsMessage::Server(tokens[1].to_string()),
         e => {
-            return Err(SdpParserInternalError::Generic(format!(
-                "dtls-message has unknown role token '{}'",
-                e
-            )));
+            return Err(SdpParserInternalError::Invalid(
+                SdpErrorKind::InvalidValue,
+                format!("dtls-message has unknown role token '{}'", e),
+            ));
         }
     }))
 }
@@ -1909,7 +3236,8 @@ fn valid_byte_string(input: &str) -> bool {
 fn parse_extmap(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
     let tokens: Vec<&str> = to_parse.split_whitespace().collect();
     if tokens.len() < 2 {
-        return Err(SdpParserInternalError::Generic(
+        return Err(SdpParserInternalError::Invalid(
+            SdpErrorKind::MissingToken,
             "Extmap needs to have at least two tokens".to_string(),
         ));
     }
@@ -1920,16 +3248,15 @@ fn parse_extmap(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError>
     } else {
         let id_dir: Vec<&str> = tokens[0].splitn(2, '/').collect();
         id = id_dir[0].parse::<u16>()?;
-        direction = Some(match id_dir[1].to_lowercase().as_ref() {
-            "recvonly" => SdpAttributeDirection::Recvonly,
-            "sendonly" => SdpAttributeDirection::Sendonly,
-            "sendrecv" => SdpAttributeDirection::Sendrecv,
-            _ => {
-                return Err(SdpParserInternalError::Generic(
-                    "Unsupported direction in extmap value".to_string(),
-                ));
-            }
-        })
+        direction = Some(id_dir[1].parse::<SdpAttributeDirection>()?)
+    }
+    // RFC8285: id 0 is reserved for padding, 1-14 use the one-byte header
+    // and 15-255 the two-byte header
+    if id == 0 || id > 255 {
+        return Err(SdpParserInternalError::Invalid(
+            SdpErrorKind::InvalidNumber,
+            "Extmap id must be in the range [1,255]".to_string(),
+        ));
     }
     // Consider replacing to_parse.split_whitespace() above with splitn on space. Would we want the pattern to split on any amout of any kind of whitespace?
     let extension_attributes = if tokens.len() == 2 {
@@ -1937,7 +3264,8 @@ fn parse_extmap(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError>
     } else {
         let ext_string: String = tokens[2..].join(" ");
         if !valid_byte_string(&ext_string) {
-            return Err(SdpParserInternalError::Generic(
+            return Err(SdpParserInternalError::Invalid(
+                SdpErrorKind::InvalidValue,
                 "Illegal character in extmap extension attributes".to_string(),
             ));
         }
@@ -1970,7 +3298,8 @@ fn parse_extmap(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError>
 fn parse_fingerprint(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
     let tokens: Vec<&str> = to_parse.split_whitespace().collect();
     if tokens.len() != 2 {
-        return Err(SdpParserInternalError::Generic(
+        return Err(SdpParserInternalError::Invalid(
+            SdpErrorKind::TokenCount,
             "Fingerprint needs to have two tokens".to_string(),
         ));
     }
@@ -1981,7 +3310,8 @@ fn parse_fingerprint(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalEr
             .split(':')
             .map(|byte_token| {
                 if byte_token.len() != 2 {
-                    return Err(SdpParserInternalError::Generic(
+                    return Err(SdpParserInternalError::Invalid(
+                        SdpErrorKind::InvalidValue,
                         "fingerpint's byte tokens must have 2 hexdigits".to_string(),
                     ));
                 }
@@ -1990,17 +3320,20 @@ fn parse_fingerprint(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalEr
             .collect::<Result<Vec<u8>, _>>()?;
 
         if bytes.len() != expected_len {
-            return Err(SdpParserInternalError::Generic(format!(
-                "fingerprint has {} bytes but should have {} bytes",
-                bytes.len(),
-                expected_len
-            )));
+            return Err(SdpParserInternalError::Invalid(
+                SdpErrorKind::InvalidValue,
+                format!(
+                    "fingerprint has {} bytes but should have {} bytes",
+                    bytes.len(),
+                    expected_len
+                ),
+            ));
         }
 
         Ok(bytes)
     };
 
-    let hash_algorithm = match tokens[0] {
+    let hash_algorithm = match tokens[0].to_lowercase().as_ref() {
         "sha-1" => SdpAttributeFingerprintHashType::Sha1,
         "sha-224" => SdpAttributeFingerprintHashType::Sha224,
         "sha-256" => SdpAttributeFingerprintHashType::Sha256,
@@ -2044,205 +3377,170 @@ fn parse_fmtp(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
 
     let payload_token = tokens[0];
 
-    // Default initiliaze SdpAttributeFmtpParameters
-    let mut parameters = SdpAttributeFmtpParameters {
-        packetization_mode: 0,
-        level_asymmetry_allowed: false,
-        profile_level_id: 0x0042_0010,
-        max_fs: 0,
-        max_cpb: 0,
-        max_dpb: 0,
-        max_br: 0,
-        max_mbps: 0,
-        usedtx: false,
-        stereo: false,
-        useinbandfec: false,
-        cbr: false,
-        max_fr: 0,
-        maxplaybackrate: 48000,
-        maxaveragebitrate: 0,
-        ptime: 0,
-        minptime: 0,
-        maxptime: 0,
-        encodings: Vec::new(),
-        dtmf_tones: "".to_string(),
-        rtx: None,
-        unknown_tokens: Vec::new(),
-    };
+    Ok(SdpAttribute::Fmtp(SdpAttributeFmtp {
+        payload_type: match payload_token {
+            "*" => SdpAttributePayloadType::Wildcard,
+            _ => SdpAttributePayloadType::PayloadType(payload_token.parse::<u8>()?),
+        },
+        parameters: parse_fmtp_parameters(tokens[1])?,
+        tokens: split_fmtp_tokens(tokens[1]),
+    }))
+}
+
+fn split_fmtp_tokens(parameter_block: &str) -> Vec<String> {
+    parameter_block
+        .split(';')
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+        .map(ToString::to_string)
+        .collect()
+}
 
-    for parameter_token in tokens[1..].iter() {
-        if parameter_token.contains('=') {
-            // Permit Leading/Trailing/Inner ';' by filtering out empty splits
-            let parameter_tokens: Vec<&str> = parameter_token
-                .split(';')
-                .filter(|token| !token.is_empty())
-                .collect();
-            for parameter_token in parameter_tokens.iter() {
-                let name_value_pair: Vec<&str> = parameter_token.splitn(2, '=').collect();
-                if name_value_pair.len() != 2 {
-                    return Err(SdpParserInternalError::Generic(
-                        "A fmtp parameter must be either a telephone event, a parameter list or a red codec list"
-                            .to_string(),
+fn is_dtmf_tone_list(to_parse: &str) -> bool {
+    // Tones and both ends of a range have to be in [0,100]
+    let parse_tone = |tone: &str| tone.parse::<u8>().ok().filter(|x| *x <= 100);
+
+    to_parse.split(',').all(|dtmf_tone| {
+        match dtmf_tone.split_once('-') {
+            // Check that the first part of the range is smaller than the second part
+            Some((lower, upper)) => match (parse_tone(lower), parse_tone(upper)) {
+                (Some(l), Some(u)) => l < u,
+                _ => false,
+            },
+            None => parse_tone(dtmf_tone).is_some(),
+        }
+    })
+}
+
+// The parameter block is either a red codec list, a list of telephone-event
+// tones or a ';' separated list of name=value pairs and bare flags. Every
+// token without a '=' which is not one of the two lists is kept as a flag.
+fn parse_fmtp_parameters(
+    parameter_block: &str,
+) -> Result<SdpAttributeFmtpParameters, SdpParserInternalError> {
+    let mut parameters = SdpAttributeFmtpParameters::default();
+    let parameter_block = parameter_block.trim();
+
+    if !parameter_block.contains('=') && parameter_block.contains('/') {
+        for encoding in parameter_block.split('/') {
+            match encoding.parse::<u8>()? {
+                x @ 0..=128 => parameters.encodings.push(x),
+                _ => {
+                    return Err(SdpParserInternalError::Invalid(
+                        SdpErrorKind::InvalidNumber,
+                        "Red codec must be in range [0,128]".to_string(),
                     ));
                 }
+            }
+        }
+        return Ok(parameters);
+    }
 
-                let parse_bool =
-                    |val: &str, param_name: &str| -> Result<bool, SdpParserInternalError> {
-                        match val.parse::<u8>()? {
-                            0 => Ok(false),
-                            1 => Ok(true),
-                            _ => Err(SdpParserInternalError::Generic(format!(
-                                "The fmtp parameter '{:}' must be 0 or 1",
-                                param_name
-                            ))),
-                        }
-                    };
+    // This is the case for the 'telephone-event' codec
+    if is_dtmf_tone_list(parameter_block) {
+        parameters.dtmf_tones = parameter_block.to_string();
+        return Ok(parameters);
+    }
+
+    // Permit Leading/Trailing/Inner ';' by filtering out empty splits
+    let parameter_tokens: Vec<&str> = parameter_block
+        .split(';')
+        .filter(|token| !token.trim().is_empty())
+        .collect();
+    for parameter_token in parameter_tokens.iter() {
+        let name_value_pair: Vec<&str> = parameter_token.splitn(2, '=').collect();
+        if name_value_pair.len() != 2 {
+            // Bare flags without a value are passed through as is
+            parameters
+                .unknown_tokens
+                .push((*parameter_token).to_string());
+            continue;
+        }
 
-                let parameter_name = name_value_pair[0];
-                let parameter_val = name_value_pair[1];
+        let parse_bool = |val: &str, param_name: &str| -> Result<bool, SdpParserInternalError> {
+            match val.parse::<u8>()? {
+                0 => Ok(false),
+                1 => Ok(true),
+                _ => Err(SdpParserInternalError::Invalid(
+                    SdpErrorKind::InvalidNumber,
+                    format!("The fmtp parameter '{:}' must be 0 or 1", param_name),
+                )),
+            }
+        };
 
-                match parameter_name.to_uppercase().as_str() {
-                    // H264
-                    "PROFILE-LEVEL-ID" => parameters.profile_level_id = match u32::from_str_radix(
-                        parameter_val,
-                        16,
-                    )? {
+        let parameter_name = name_value_pair[0];
+        let parameter_val = name_value_pair[1];
+
+        match parameter_name.to_uppercase().as_str() {
+            // H264
+            "PROFILE-LEVEL-ID" => {
+                parameters.profile_level_id =
+                    match u32::from_str_radix(parameter_val, 16)? {
                         x @ 0..=0x00ff_ffff => x,
-                        _ => return Err(SdpParserInternalError::Generic(
+                        _ => return Err(SdpParserInternalError::Invalid(
+                            SdpErrorKind::InvalidNumber,
                             "The fmtp parameter 'profile-level-id' must be in range [0,0xffffff]"
                                 .to_string(),
                         )),
-                    },
-                    "PACKETIZATION-MODE" => {
-                        parameters.packetization_mode = match parameter_val.parse::<u32>()? {
-                            x @ 0..=2 => x,
-                            _ => {
-                                return Err(SdpParserInternalError::Generic(
-                                    "The fmtp parameter 'packetization-mode' must be 0,1 or 2"
-                                        .to_string(),
-                                ));
-                            }
-                        }
-                    }
-                    "LEVEL-ASYMMETRY-ALLOWED" => {
-                        parameters.level_asymmetry_allowed =
-                            parse_bool(parameter_val, "level-asymmetry-allowed")?
-                    }
-                    "MAX-MBPS" => parameters.max_mbps = parameter_val.parse::<u32>()?,
-                    "MAX-FS" => parameters.max_fs = parameter_val.parse::<u32>()?,
-                    "MAX-CPB" => parameters.max_cpb = parameter_val.parse::<u32>()?,
-                    "MAX-DPB" => parameters.max_dpb = parameter_val.parse::<u32>()?,
-                    "MAX-BR" => parameters.max_br = parameter_val.parse::<u32>()?,
-
-                    // VP8 and VP9
-                    "MAX-FR" => parameters.max_fr = parameter_val.parse::<u32>()?,
-
-                    //Opus https://tools.ietf.org/html/rfc7587
-                    "MAXPLAYBACKRATE" => {
-                        parameters.maxplaybackrate = parameter_val.parse::<u32>()?
-                    }
-                    "MAXAVERAGEBITRATE" => {
-                        parameters.maxaveragebitrate = parameter_val.parse::<u32>()?
-                    }
-                    "PTIME" => parameters.ptime = parameter_val.parse::<u32>()?,
-                    "MAXPTIME" => parameters.maxptime = parameter_val.parse::<u32>()?,
-                    "MINPTIME" => parameters.minptime = parameter_val.parse::<u32>()?,
-                    "USEDTX" => parameters.usedtx = parse_bool(parameter_val, "usedtx")?,
-                    "STEREO" => parameters.stereo = parse_bool(parameter_val, "stereo")?,
-                    "USEINBANDFEC" => {
-                        parameters.useinbandfec = parse_bool(parameter_val, "useinbandfec")?
                     }
-                    "CBR" => parameters.cbr = parse_bool(parameter_val, "cbr")?,
-                    "APT" => {
-                        parameters.rtx = Some(RtxFmtpParameters {
-                            apt: parameter_val.parse::<u8>()?,
-                            rtx_time: None,
-                        })
-                    }
-                    "RTX-TIME" => {
-                        if let Some(ref mut rtx) = parameters.rtx {
-                            rtx.rtx_time = Some(parameter_val.parse::<u32>()?)
-                        } else {
-                            return Err(SdpParserInternalError::Generic(
-                                "RTX codec must have an APT field".to_string(),
-                            ));
-                        }
-                    }
-                    _ => parameters
-                        .unknown_tokens
-                        .push((*parameter_token).to_string()),
-                }
             }
-        } else if parameter_token.contains('/') {
-            let encodings: Vec<&str> = parameter_token.split('/').collect();
-
-            for encoding in encodings {
-                match encoding.parse::<u8>()? {
-                    x @ 0..=128 => parameters.encodings.push(x),
+            "PACKETIZATION-MODE" => {
+                parameters.packetization_mode = match parameter_val.parse::<u32>()? {
+                    x @ 0..=2 => x,
                     _ => {
-                        return Err(SdpParserInternalError::Generic(
-                            "Red codec must be in range [0,128]".to_string(),
+                        return Err(SdpParserInternalError::Invalid(
+                            SdpErrorKind::InvalidNumber,
+                            "The fmtp parameter 'packetization-mode' must be 0,1 or 2".to_string(),
                         ));
                     }
                 }
             }
-        } else {
-            // This is the case for the 'telephone-event' codec
-            let dtmf_tones: Vec<&str> = parameter_token.split(',').collect();
-            let mut dtmf_tone_is_ok = true;
-
-            // This closure verifies the output of some_number_as_string.parse::<u8>().ok() like calls
-            let validate_digits = |digit_option: Option<u8>| -> Option<u8> {
-                match digit_option {
-                    Some(x) => match x {
-                        0..=100 => Some(x),
-                        _ => None,
-                    },
-                    None => None,
-                }
-            };
-
-            // This loop does some sanity checking on the passed dtmf tones
-            for dtmf_tone in dtmf_tones {
-                let dtmf_tone_range: Vec<&str> = dtmf_tone.splitn(2, '-').collect();
-
-                dtmf_tone_is_ok = match dtmf_tone_range.len() {
-                    // In this case the dtmf tone is a range
-                    2 => {
-                        match validate_digits(dtmf_tone_range[0].parse::<u8>().ok()) {
-                            Some(l) => match validate_digits(dtmf_tone_range[1].parse::<u8>().ok())
-                            {
-                                Some(u) => {
-                                    // Check that the first part of the range is smaller than the second part
-                                    l < u
-                                }
-                                None => false,
-                            },
-                            None => false,
-                        }
-                    }
-                    // In this case the dtmf tone is a single tone
-                    1 => validate_digits(dtmf_tone.parse::<u8>().ok()).is_some(),
-                    _ => false,
-                };
-
-                if !dtmf_tone_is_ok {
-                    break;
+            "LEVEL-ASYMMETRY-ALLOWED" => {
+                parameters.level_asymmetry_allowed =
+                    parse_bool(parameter_val, "level-asymmetry-allowed")?
+            }
+            "MAX-MBPS" => parameters.max_mbps = parameter_val.parse::<u32>()?,
+            "MAX-FS" => parameters.max_fs = parameter_val.parse::<u32>()?,
+            "MAX-CPB" => parameters.max_cpb = parameter_val.parse::<u32>()?,
+            "MAX-DPB" => parameters.max_dpb = parameter_val.parse::<u32>()?,
+            "MAX-BR" => parameters.max_br = parameter_val.parse::<u32>()?,
+
+            // VP8 and VP9
+            "MAX-FR" => parameters.max_fr = parameter_val.parse::<u32>()?,
+            "PROFILE-ID" => parameters.profile_id = Some(parameter_val.parse::<u32>()?),
+
+            //Opus https://tools.ietf.org/html/rfc7587
+            "MAXPLAYBACKRATE" => parameters.maxplaybackrate = parameter_val.parse::<u32>()?,
+            "MAXAVERAGEBITRATE" => parameters.maxaveragebitrate = parameter_val.parse::<u32>()?,
+            "PTIME" => parameters.ptime = parameter_val.parse::<u32>()?,
+            "MAXPTIME" => parameters.maxptime = parameter_val.parse::<u32>()?,
+            "MINPTIME" => parameters.minptime = parameter_val.parse::<u32>()?,
+            "USEDTX" => parameters.usedtx = parse_bool(parameter_val, "usedtx")?,
+            "STEREO" => parameters.stereo = parse_bool(parameter_val, "stereo")?,
+            "USEINBANDFEC" => parameters.useinbandfec = parse_bool(parameter_val, "useinbandfec")?,
+            "CBR" => parameters.cbr = parse_bool(parameter_val, "cbr")?,
+            "APT" => {
+                parameters.rtx = Some(RtxFmtpParameters {
+                    apt: parameter_val.parse::<u8>()?,
+                    rtx_time: None,
+                })
+            }
+            "RTX-TIME" => {
+                if let Some(ref mut rtx) = parameters.rtx {
+                    rtx.rtx_time = Some(parameter_val.parse::<u32>()?)
+                } else {
+                    return Err(SdpParserInternalError::Invalid(
+                        SdpErrorKind::MissingToken,
+                        "RTX codec must have an APT field".to_string(),
+                    ));
                 }
             }
-
-            // Set the parsed dtmf tones or in case the parsing was insuccessfull, set it to the default "0-15"
-            parameters.dtmf_tones = if dtmf_tone_is_ok {
-                (*parameter_token).to_string()
-            } else {
-                "0-15".to_string()
-            };
+            _ => parameters
+                .unknown_tokens
+                .push((*parameter_token).to_string()),
         }
     }
-    Ok(SdpAttribute::Fmtp(SdpAttributeFmtp {
-        payload_type: payload_token.parse::<u8>()?,
-        parameters,
-    }))
+    Ok(parameters)
 }
 
 ///////////////////////////////////////////////////////////////////////////
@@ -2257,7 +3555,8 @@ fn parse_group(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
     let mut tokens = to_parse.split_whitespace();
     let semantics = match tokens.next() {
         None => {
-            return Err(SdpParserInternalError::Generic(
+            return Err(SdpParserInternalError::Invalid(
+                SdpErrorKind::MissingToken,
                 "Group attribute is missing semantics token".to_string(),
             ));
         }
@@ -2283,6 +3582,32 @@ fn parse_group(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
     }))
 }
 
+/// Whether the session level attributes mark the peer as an ICE lite
+/// implementation.
+pub fn is_ice_lite(attrs: &[SdpAttribute]) -> bool {
+    attrs.contains(&SdpAttribute::IceLite)
+}
+
+/// Returns the values of all mid attributes in the order they appear.
+pub fn mids(attrs: &[SdpAttribute]) -> Vec<String> {
+    attrs
+        .iter()
+        .filter_map(SdpAttribute::mid)
+        .map(ToString::to_string)
+        .collect()
+}
+
+/// Returns the identification tags of the first BUNDLE group, if any.
+pub fn bundle_group(attrs: &[SdpAttribute]) -> Option<Vec<String>> {
+    attrs.iter().find_map(|attr| match *attr {
+        SdpAttribute::Group(SdpAttributeGroup {
+            semantics: SdpAttributeGroupSemantic::Bundle,
+            ref tags,
+        }) => Some(tags.clone()),
+        _ => None,
+    })
+}
+
 ///////////////////////////////////////////////////////////////////////////
 // a=ice-options, draft-ietf-mmusic-ice-sip-sdp
 //-------------------------------------------------------------------------
@@ -2290,17 +3615,17 @@ fn parse_group(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
 //                           0*(SP ice-option-tag)
 //  ice-option-tag        = 1*ice-char
 fn parse_ice_options(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
-    if to_parse.is_empty() {
-        return Err(SdpParserInternalError::Generic(
-            "ice-options is required to have a value".to_string(),
+    let options: Vec<String> = to_parse
+        .split_whitespace()
+        .map(ToString::to_string)
+        .collect();
+    if options.is_empty() {
+        return Err(SdpParserInternalError::Invalid(
+            SdpErrorKind::MissingToken,
+            "ice-options requires at least one option".to_string(),
         ));
     }
-    Ok(SdpAttribute::IceOptions(
-        to_parse
-            .split_whitespace()
-            .map(ToString::to_string)
-            .collect(),
-    ))
+    Ok(SdpAttribute::IceOptions(options))
 }
 
 ///////////////////////////////////////////////////////////////////////////
@@ -2311,7 +3636,8 @@ fn parse_ice_options(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalEr
 fn parse_ice_pacing(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
     let parsed = to_parse.parse::<u64>()?;
     if parsed >= 1_00_00_00_00_00 {
-        return Err(SdpParserInternalError::Generic(
+        return Err(SdpParserInternalError::Invalid(
+            SdpErrorKind::InvalidNumber,
             "ice-pacing value is not a 10 digit integer".to_string(),
         ));
     }
@@ -2359,7 +3685,8 @@ fn parse_image_attr_xyrange(
 ) -> Result<SdpAttributeImageAttrXyRange, SdpParserInternalError> {
     if to_parse.starts_with('[') {
         let value_tokens = parse_imagettr_braced_token(to_parse).ok_or_else(|| {
-            SdpParserInternalError::Generic(
+            SdpParserInternalError::Invalid(
+                SdpErrorKind::InvalidValue,
                 "imageattr's xyrange has no closing tag ']'".to_string(),
             )
         })?;
@@ -2381,7 +3708,8 @@ fn parse_image_attr_xyrange(
                     None,
                 ))
             } else {
-                Err(SdpParserInternalError::Generic(
+                Err(SdpParserInternalError::Invalid(
+                    SdpErrorKind::InvalidValue,
                     "imageattr's xyrange must contain 2 or 3 fields".to_string(),
                 ))
             }
@@ -2393,7 +3721,8 @@ fn parse_image_attr_xyrange(
                 .collect::<Result<Vec<u32>, _>>()?;
 
             if values.len() < 2 {
-                return Err(SdpParserInternalError::Generic(
+                return Err(SdpParserInternalError::Invalid(
+                    SdpErrorKind::InvalidValue,
                     "imageattr's discrete value list must have at least two elements".to_string(),
                 ));
             }
@@ -2413,20 +3742,28 @@ fn parse_image_attr_set(
     let mut tokens = parse_imageattr_tokens(to_parse, ',').into_iter();
 
     let x_token = tokens.next().ok_or_else(|| {
-        SdpParserInternalError::Generic("imageattr set is missing the 'x=' token".to_string())
+        SdpParserInternalError::Invalid(
+            SdpErrorKind::MissingToken,
+            "imageattr set is missing the 'x=' token".to_string(),
+        )
     })?;
     if !x_token.starts_with("x=") {
-        return Err(SdpParserInternalError::Generic(
+        return Err(SdpParserInternalError::Invalid(
+            SdpErrorKind::InvalidValue,
             "The first token in an imageattr set must begin with 'x='".to_string(),
         ));
     }
     let x = parse_image_attr_xyrange(&x_token[2..])?;
 
     let y_token = tokens.next().ok_or_else(|| {
-        SdpParserInternalError::Generic("imageattr set is missing the 'y=' token".to_string())
+        SdpParserInternalError::Invalid(
+            SdpErrorKind::MissingToken,
+            "imageattr set is missing the 'y=' token".to_string(),
+        )
     })?;
     if !y_token.starts_with("y=") {
-        return Err(SdpParserInternalError::Generic(
+        return Err(SdpParserInternalError::Invalid(
+            SdpErrorKind::InvalidValue,
             "The second token in an imageattr set must begin with 'y='".to_string(),
         ));
     }
@@ -2440,7 +3777,8 @@ fn parse_image_attr_set(
         let minmax_pair: Vec<&str> = resolution_range.split('-').collect();
 
         if minmax_pair.len() != 2 {
-            return Err(SdpParserInternalError::Generic(
+            return Err(SdpParserInternalError::Invalid(
+                SdpErrorKind::InvalidValue,
                 "imageattr's par and sar ranges must have two components".to_string(),
             ));
         }
@@ -2449,7 +3787,8 @@ fn parse_image_attr_set(
         let max = minmax_pair[1].parse::<f32>()?;
 
         if min >= max {
-            return Err(SdpParserInternalError::Generic(
+            return Err(SdpParserInternalError::Invalid(
+                SdpErrorKind::InvalidValue,
                 "In imageattr's par and sar ranges, first must be < than the second".to_string(),
             ));
         }
@@ -2461,7 +3800,8 @@ fn parse_image_attr_set(
         if let Some(value_token) = current_token.strip_prefix("sar=") {
             if value_token.starts_with('[') {
                 let sar_values = parse_imagettr_braced_token(value_token).ok_or_else(|| {
-                    SdpParserInternalError::Generic(
+                    SdpParserInternalError::Invalid(
+                        SdpErrorKind::InvalidValue,
                         "imageattr's sar value is missing closing tag ']'".to_string(),
                     )
                 })?;
@@ -2478,7 +3818,8 @@ fn parse_image_attr_set(
                         .collect::<Result<Vec<f32>, _>>()?;
 
                     if values.len() < 2 {
-                        return Err(SdpParserInternalError::Generic(
+                        return Err(SdpParserInternalError::Invalid(
+                            SdpErrorKind::InvalidValue,
                             "imageattr's sar discrete value list must have at least two values"
                                 .to_string(),
                         ));
@@ -2488,7 +3829,8 @@ fn parse_image_attr_set(
                     let mut last_value = 0.0;
                     for value in &values {
                         if last_value >= *value {
-                            return Err(SdpParserInternalError::Generic(
+                            return Err(SdpParserInternalError::Invalid(
+                                SdpErrorKind::InvalidValue,
                                 "imageattr's sar discrete value list must contain ascending values"
                                     .to_string(),
                             ));
@@ -2504,13 +3846,15 @@ fn parse_image_attr_set(
             }
         } else if let Some(braced_value_token) = current_token.strip_prefix("par=") {
             if !braced_value_token.starts_with('[') {
-                return Err(SdpParserInternalError::Generic(
+                return Err(SdpParserInternalError::Invalid(
+                    SdpErrorKind::InvalidValue,
                     "imageattr's par value must start with '['".to_string(),
                 ));
             }
 
             let par_values = parse_imagettr_braced_token(braced_value_token).ok_or_else(|| {
-                SdpParserInternalError::Generic(
+                SdpParserInternalError::Invalid(
+                    SdpErrorKind::InvalidValue,
                     "imageattr's par value must be enclosed with ']'".to_string(),
                 )
             })?;
@@ -2535,14 +3879,18 @@ where
 {
     let parse_set = |set_token: &str| -> Result<SdpAttributeImageAttrSet, SdpParserInternalError> {
         parse_image_attr_set(parse_imagettr_braced_token(set_token).ok_or_else(|| {
-            SdpParserInternalError::Generic("imageattr sets must be enclosed by ']'".to_string())
+            SdpParserInternalError::Invalid(
+                SdpErrorKind::InvalidValue,
+                "imageattr sets must be enclosed by ']'".to_string(),
+            )
         })?)
     };
 
     match tokens
         .next()
         .ok_or_else(|| {
-            SdpParserInternalError::Generic(
+            SdpParserInternalError::Invalid(
+                SdpErrorKind::MissingToken,
                 "imageattr must have a parameter set after a direction token".to_string(),
             )
         })?
@@ -2639,7 +3987,10 @@ fn parse_image_attr(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalErr
         tokens
             .next()
             .ok_or_else(|| {
-                SdpParserInternalError::Generic("imageattr requires a payload token".to_string())
+                SdpParserInternalError::Invalid(
+                    SdpErrorKind::MissingToken,
+                    "imageattr requires a payload token".to_string(),
+                )
             })?
             .as_str(),
     )?;
@@ -2647,7 +3998,8 @@ fn parse_image_attr(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalErr
         tokens
             .next()
             .ok_or_else(|| {
-                SdpParserInternalError::Generic(
+                SdpParserInternalError::Invalid(
+                    SdpErrorKind::InvalidValue,
                     "imageattr's second token must be a direction token".to_string(),
                 )
             })?
@@ -2661,7 +4013,8 @@ fn parse_image_attr(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalErr
     // Check if there is a second direction defined
     if let Some(direction_token) = tokens.next() {
         if parse_single_direction(direction_token.as_str())? == first_direction {
-            return Err(SdpParserInternalError::Generic(
+            return Err(SdpParserInternalError::Invalid(
+                SdpErrorKind::InvalidValue,
                 "imageattr's second direction token must be different from the first one"
                     .to_string(),
             ));
@@ -2671,7 +4024,8 @@ fn parse_image_attr(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalErr
     }
 
     if tokens.next().is_some() {
-        return Err(SdpParserInternalError::Generic(
+        return Err(SdpParserInternalError::Invalid(
+            SdpErrorKind::UnexpectedToken,
             "imageattr must not contain any token after the second set list".to_string(),
         ));
     }
@@ -2700,11 +4054,13 @@ fn parse_msid(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
     let mut tokens = to_parse.split_whitespace();
     let id = match tokens.next() {
         None => {
-            return Err(SdpParserInternalError::Generic(
+            return Err(SdpParserInternalError::Invalid(
+                SdpErrorKind::MissingToken,
                 "Msid attribute is missing msid-id token".to_string(),
             ));
         }
-        Some(x) => x.to_string(),
+        Some("-") => MsidId::NoStream,
+        Some(x) => MsidId::Id(x.to_string()),
     };
     let appdata = match tokens.next() {
         None => None,
@@ -2722,7 +4078,8 @@ fn parse_msid(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
 fn parse_msid_semantic(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
     let tokens: Vec<_> = to_parse.split_whitespace().collect();
     if tokens.is_empty() {
-        return Err(SdpParserInternalError::Generic(
+        return Err(SdpParserInternalError::Invalid(
+            SdpErrorKind::MissingToken,
             "Msid-semantic attribute is missing msid-semantic token".to_string(),
         ));
     }
@@ -2773,7 +4130,8 @@ fn parse_rid(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
     let tokens: Vec<&str> = to_parse.splitn(3, ' ').collect();
 
     if tokens.len() < 2 {
-        return Err(SdpParserInternalError::Generic(
+        return Err(SdpParserInternalError::Invalid(
+            SdpErrorKind::MissingToken,
             "A rid attribute must at least have an id and a direction token.".to_string(),
         ));
     }
@@ -2809,7 +4167,8 @@ fn parse_rid(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
             // TODO: Bug 1225877. Add support for params without '='
             let param_value_pair: Vec<&str> = param.splitn(2, '=').collect();
             if param_value_pair.len() != 2 {
-                return Err(SdpParserInternalError::Generic(
+                return Err(SdpParserInternalError::Invalid(
+                    SdpErrorKind::InvalidValue,
                     "A rid parameter needs to be of form 'param=value'".to_string(),
                 ));
             }
@@ -2848,7 +4207,8 @@ fn parse_remote_candidates(to_parse: &str) -> Result<SdpAttribute, SdpParserInte
     let mut tokens = to_parse.split_whitespace();
     let component = match tokens.next() {
         None => {
-            return Err(SdpParserInternalError::Generic(
+            return Err(SdpParserInternalError::Invalid(
+                SdpErrorKind::MissingToken,
                 "Remote-candidate attribute is missing component ID".to_string(),
             ));
         }
@@ -2856,7 +4216,8 @@ fn parse_remote_candidates(to_parse: &str) -> Result<SdpAttribute, SdpParserInte
     };
     let address = match tokens.next() {
         None => {
-            return Err(SdpParserInternalError::Generic(
+            return Err(SdpParserInternalError::Invalid(
+                SdpErrorKind::MissingToken,
                 "Remote-candidate attribute is missing connection address".to_string(),
             ));
         }
@@ -2864,14 +4225,16 @@ fn parse_remote_candidates(to_parse: &str) -> Result<SdpAttribute, SdpParserInte
     };
     let port = match tokens.next() {
         None => {
-            return Err(SdpParserInternalError::Generic(
+            return Err(SdpParserInternalError::Invalid(
+                SdpErrorKind::MissingToken,
                 "Remote-candidate attribute is missing port number".to_string(),
             ));
         }
         Some(x) => x.parse::<u32>()?,
     };
     if port > 65535 {
-        return Err(SdpParserInternalError::Generic(
+        return Err(SdpParserInternalError::Invalid(
+            SdpErrorKind::PortOutOfRange,
             "Remote-candidate port can only be a bit 16bit number".to_string(),
         ));
     };
@@ -2886,18 +4249,25 @@ fn parse_remote_candidates(to_parse: &str) -> Result<SdpAttribute, SdpParserInte
 // a=rtpmap, RFC4566
 //-------------------------------------------------------------------------
 // a=rtpmap:<payload type> <encoding name>/<clock rate> [/<encoding parameters>]
-fn parse_rtpmap(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
+//
+// Outside of strict mode a missing clock rate is tolerated and stored as None.
+fn parse_rtpmap(
+    to_parse: &str,
+    config: &SdpParserConfig,
+) -> Result<SdpAttribute, SdpParserInternalError> {
     let mut tokens = to_parse.split_whitespace();
     let payload_type: u8 = match tokens.next() {
         None => {
-            return Err(SdpParserInternalError::Generic(
+            return Err(SdpParserInternalError::Invalid(
+                SdpErrorKind::MissingToken,
                 "Rtpmap missing payload type".to_string(),
             ));
         }
         Some(x) => {
             let pt = x.parse::<u8>()?;
             if pt > 127 {
-                return Err(SdpParserInternalError::Generic(
+                return Err(SdpParserInternalError::Invalid(
+                    SdpErrorKind::InvalidNumber,
                     "Rtpmap payload type must be less then 127".to_string(),
                 ));
             };
@@ -2906,7 +4276,8 @@ fn parse_rtpmap(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError>
     };
     let mut parameters = match tokens.next() {
         None => {
-            return Err(SdpParserInternalError::Generic(
+            return Err(SdpParserInternalError::Invalid(
+                SdpErrorKind::MissingToken,
                 "Rtpmap missing payload type".to_string(),
             ));
         }
@@ -2914,23 +4285,44 @@ fn parse_rtpmap(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError>
     };
     let name = match parameters.next() {
         None => {
-            return Err(SdpParserInternalError::Generic(
+            return Err(SdpParserInternalError::Invalid(
+                SdpErrorKind::MissingToken,
                 "Rtpmap missing codec name".to_string(),
             ));
         }
         Some(x) => x.to_string(),
     };
     let frequency = match parameters.next() {
-        None => {
-            return Err(SdpParserInternalError::Generic(
-                "Rtpmap missing codec name".to_string(),
+        None if config.strict => {
+            return Err(SdpParserInternalError::Invalid(
+                SdpErrorKind::MissingToken,
+                "Rtpmap missing clock rate".to_string(),
             ));
         }
-        Some(x) => x.parse::<u32>()?,
+        None => None,
+        Some("") => {
+            return Err(SdpParserInternalError::Invalid(
+                SdpErrorKind::MissingToken,
+                "Rtpmap has an empty clock rate token".to_string(),
+            ));
+        }
+        Some(x) => Some(x.parse::<u32>()?),
     };
-    let mut rtpmap = SdpAttributeRtpmap::new(payload_type, name, frequency);
-    if let Some(x) = parameters.next() {
-        rtpmap.set_channels(x.parse::<u32>()?)
+    let mut rtpmap = SdpAttributeRtpmap {
+        payload_type,
+        codec_name: name,
+        frequency,
+        channels: None,
+    };
+    match parameters.next() {
+        None => (),
+        Some("") => {
+            return Err(SdpParserInternalError::Invalid(
+                SdpErrorKind::MissingToken,
+                "Rtpmap has an empty channels token".to_string(),
+            ));
+        }
+        Some(x) => rtpmap.set_channels(x.parse::<u32>()?),
     };
     Ok(SdpAttribute::Rtpmap(rtpmap))
 }
@@ -2940,11 +4332,15 @@ fn parse_rtpmap(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError>
 //-------------------------------------------------------------------------
 //   rtcp-attribute =  "a=rtcp:" port  [nettype space addrtype space
 //                         connection-address] CRLF
-fn parse_rtcp(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
+fn parse_rtcp(
+    to_parse: &str,
+    config: &SdpParserConfig,
+) -> Result<SdpAttribute, SdpParserInternalError> {
     let mut tokens = to_parse.split_whitespace();
     let port = match tokens.next() {
         None => {
-            return Err(SdpParserInternalError::Generic(
+            return Err(SdpParserInternalError::Invalid(
+                SdpErrorKind::MissingToken,
                 "Rtcp attribute is missing port number".to_string(),
             ));
         }
@@ -2957,15 +4353,20 @@ fn parse_rtcp(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
             parse_network_type(x)?;
             match tokens.next() {
                 None => {
-                    return Err(SdpParserInternalError::Generic(
+                    return Err(SdpParserInternalError::Invalid(
+                        SdpErrorKind::MissingToken,
                         "Rtcp attribute is missing address type token".to_string(),
                     ));
                 }
                 Some(x) => {
                     let addrtype = AddressType::from_str(x)?;
                     let addr = match tokens.next() {
+                        // Some legacy stacks omit the address, which is only
+                        // tolerated outside of strict mode.
+                        None if !config.strict => return Ok(SdpAttribute::Rtcp(rtcp)),
                         None => {
-                            return Err(SdpParserInternalError::Generic(
+                            return Err(SdpParserInternalError::Invalid(
+                                SdpErrorKind::MissingToken,
                                 "Rtcp attribute is missing ip address token".to_string(),
                             ));
                         }
@@ -3019,6 +4420,7 @@ fn parse_rtcp_fb(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError>
     let feedback_type = match tokens.get(1) {
         Some(x) => match *x {
             "ack" => SdpAttributeRtcpFbType::Ack,
+            "app" => SdpAttributeRtcpFbType::App,
             "ccm" => SdpAttributeRtcpFbType::Ccm,
             "nack" => SdpAttributeRtcpFbType::Nack,
             "trr-int" => SdpAttributeRtcpFbType::TrrInt,
@@ -3032,7 +4434,8 @@ fn parse_rtcp_fb(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError>
             }
         },
         None => {
-            return Err(SdpParserInternalError::Generic(
+            return Err(SdpParserInternalError::Invalid(
+                SdpErrorKind::MissingToken,
                 "Error parsing rtcpfb: no feedback type".to_string(),
             ));
         }
@@ -3056,6 +4459,11 @@ fn parse_rtcp_fb(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError>
                 ));
             }
         },
+        // Application layer feedback with an application defined parameter
+        SdpAttributeRtcpFbType::App => match tokens.get(2) {
+            Some(x) => (*x).to_string(),
+            None => "".to_string(),
+        },
         SdpAttributeRtcpFbType::Ccm => match tokens.get(2) {
             Some(x) => match *x {
                 "fir" | "tmmbr" | "tstr" | "vbcm" => (*x).to_string(),
@@ -3084,14 +4492,15 @@ fn parse_rtcp_fb(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError>
             Some(x) => match x {
                 _ if x.parse::<u32>().is_ok() => (*x).to_string(),
                 _ => {
-                    return Err(SdpParserInternalError::Generic(format!(
-                        "Unknown rtcpfb trr-int parameter: {:?}",
-                        x
-                    )));
+                    return Err(SdpParserInternalError::Invalid(
+                        SdpErrorKind::InvalidValue,
+                        format!("Unknown rtcpfb trr-int parameter: {:?}", x),
+                    ));
                 }
             },
             None => {
-                return Err(SdpParserInternalError::Generic(
+                return Err(SdpParserInternalError::Invalid(
+                    SdpErrorKind::MissingToken,
                     "The rtcpfb trr-int feedback type needs a parameter".to_string(),
                 ));
             }
@@ -3133,13 +4542,15 @@ fn parse_rtcp_fb(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError>
 fn parse_sctpmap(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
     let tokens: Vec<&str> = to_parse.split_whitespace().collect();
     if tokens.len() != 3 {
-        return Err(SdpParserInternalError::Generic(
+        return Err(SdpParserInternalError::Invalid(
+            SdpErrorKind::TokenCount,
             "Sctpmap needs to have three tokens".to_string(),
         ));
     }
     let port = tokens[0].parse::<u16>()?;
     if tokens[1].to_lowercase() != "webrtc-datachannel" {
-        return Err(SdpParserInternalError::Generic(
+        return Err(SdpParserInternalError::Invalid(
+            SdpErrorKind::InvalidValue,
             "Unsupported sctpmap type token".to_string(),
         ));
     }
@@ -3162,7 +4573,8 @@ fn parse_setup(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
             "holdconn" => SdpAttributeSetup::Holdconn,
             "passive" => SdpAttributeSetup::Passive,
             _ => {
-                return Err(SdpParserInternalError::Generic(
+                return Err(SdpParserInternalError::Invalid(
+                    SdpErrorKind::InvalidValue,
                     "Unsupported setup value".to_string(),
                 ));
             }
@@ -3179,21 +4591,52 @@ fn parse_simulcast_version_list(
             .map(SdpAttributeSimulcastVersion::new)
             .collect()
     };
-    if to_parse.contains('=') {
+    let versions: Vec<SdpAttributeSimulcastVersion> = if to_parse.contains('=') {
         let mut descriptor_versionlist_pair = to_parse.splitn(2, '=');
         match descriptor_versionlist_pair.next().unwrap() {
             // TODO Bug 1470568
-            "rid" => Ok(make_version_list(
-                descriptor_versionlist_pair.next().unwrap(),
-            )),
-            descriptor => Err(SdpParserInternalError::Generic(format!(
-                "Simulcast attribute has unknown list descriptor '{:?}'",
-                descriptor
-            ))),
+            "rid" => make_version_list(descriptor_versionlist_pair.next().unwrap()),
+            descriptor => {
+                return Err(SdpParserInternalError::Invalid(
+                    SdpErrorKind::InvalidValue,
+                    format!(
+                        "Simulcast attribute has unknown list descriptor '{:?}'",
+                        descriptor
+                    ),
+                ))
+            }
         }
     } else {
-        Ok(make_version_list(to_parse))
+        make_version_list(to_parse)
+    };
+    if versions
+        .iter()
+        .flat_map(|version| version.ids.iter())
+        .any(|id| id.id.is_empty())
+    {
+        return Err(SdpParserInternalError::Invalid(
+            SdpErrorKind::InvalidValue,
+            "Simulcast attribute contains an empty id".to_string(),
+        ));
+    }
+    Ok(versions)
+}
+
+// Some implementations put spaces around the list separators, which would
+// otherwise split a single list into multiple tokens.
+fn remove_whitespace_around_simulcast_separators(to_parse: &str) -> String {
+    let is_separator = |c: char| c == ';' || c == ',' || c == '=';
+    let mut result = String::with_capacity(to_parse.len());
+    for c in to_parse.trim().chars() {
+        if is_separator(c) {
+            let trimmed_len = result.trim_end().len();
+            result.truncate(trimmed_len);
+            result.push(c);
+        } else if !(c.is_whitespace() && result.ends_with(is_separator)) {
+            result.push(c);
+        }
     }
+    result
 }
 
 ///////////////////////////////////////////////////////////////////////////
@@ -3223,11 +4666,13 @@ fn parse_simulcast_version_list(
 // ; rid-id defined in [I-D.ietf-mmusic-rid]
 fn parse_simulcast(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
     // TODO: Bug 1225877: Stop accepting all kinds of whitespace here, and only accept SP
-    let mut tokens = to_parse.trim().split_whitespace();
+    let to_parse = remove_whitespace_around_simulcast_separators(to_parse);
+    let mut tokens = to_parse.split_whitespace();
     let first_direction = match tokens.next() {
         Some(x) => parse_single_direction(x)?,
         None => {
-            return Err(SdpParserInternalError::Generic(
+            return Err(SdpParserInternalError::Invalid(
+                SdpErrorKind::MissingToken,
                 "Simulcast attribute is missing send/recv value".to_string(),
             ));
         }
@@ -3236,7 +4681,8 @@ fn parse_simulcast(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalErro
     let first_version_list = match tokens.next() {
         Some(x) => parse_simulcast_version_list(x)?,
         None => {
-            return Err(SdpParserInternalError::Generic(
+            return Err(SdpParserInternalError::Invalid(
+                SdpErrorKind::MissingToken,
                 "Simulcast attribute must have an alternatives list after the direction token"
                     .to_string(),
             ));
@@ -3246,7 +4692,8 @@ fn parse_simulcast(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalErro
     let mut second_version_list = Vec::new();
     if let Some(x) = tokens.next() {
         if parse_single_direction(x)? == first_direction {
-            return Err(SdpParserInternalError::Generic(
+            return Err(SdpParserInternalError::Invalid(
+                SdpErrorKind::UnexpectedToken,
                 "Simulcast attribute has defined two times the same direction".to_string(),
             ));
         }
@@ -3254,11 +4701,14 @@ fn parse_simulcast(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalErro
         second_version_list = match tokens.next() {
             Some(x) => parse_simulcast_version_list(x)?,
             None => {
-                return Err(SdpParserInternalError::Generic(format!(
-                    "{:?}{:?}",
-                    "Simulcast has defined a second direction but",
-                    "no second list of simulcast stream versions"
-                )));
+                return Err(SdpParserInternalError::Invalid(
+                    SdpErrorKind::MissingToken,
+                    format!(
+                        "{:?}{:?}",
+                        "Simulcast has defined a second direction but",
+                        "no second list of simulcast stream versions"
+                    ),
+                ));
             }
         }
     }
@@ -3287,7 +4737,8 @@ fn parse_ssrc(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
     let mut tokens = to_parse.splitn(2, ' ');
     let ssrc_id = match tokens.next() {
         None => {
-            return Err(SdpParserInternalError::Generic(
+            return Err(SdpParserInternalError::Invalid(
+                SdpErrorKind::MissingToken,
                 "Ssrc attribute is missing ssrc-id value".to_string(),
             ));
         }
@@ -3301,8 +4752,306 @@ fn parse_ssrc(to_parse: &str) -> Result<SdpAttribute, SdpParserInternalError> {
     Ok(SdpAttribute::Ssrc(ssrc))
 }
 
+/// Collects the payload types referenced by rtpmap, fmtp and rtcp-fb
+/// attributes. Wildcard fmtp and rtcp-fb attributes are not included.
+pub fn referenced_payload_types(attrs: &[SdpAttribute]) -> BTreeSet<u8> {
+    attrs
+        .iter()
+        .filter_map(|attr| match *attr {
+            SdpAttribute::Rtpmap(ref rtpmap) => Some(rtpmap.payload_type),
+            SdpAttribute::Fmtp(SdpAttributeFmtp {
+                payload_type: SdpAttributePayloadType::PayloadType(pt),
+                ..
+            })
+            | SdpAttribute::Rtcpfb(SdpAttributeRtcpFb {
+                payload_type: SdpAttributePayloadType::PayloadType(pt),
+                ..
+            }) => Some(pt),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Returns the rtcp-fb attributes which apply to the given payload type,
+/// including wildcard ones, in the order they appear.
+pub fn rtcp_fb_for(attrs: &[SdpAttribute], pt: u8) -> Vec<&SdpAttributeRtcpFb> {
+    attrs
+        .iter()
+        .filter_map(|attr| match *attr {
+            SdpAttribute::Rtcpfb(ref rtcpfb) => match rtcpfb.payload_type {
+                SdpAttributePayloadType::Wildcard => Some(rtcpfb),
+                SdpAttributePayloadType::PayloadType(p) if p == pt => Some(rtcpfb),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect()
+}
+
+fn codec_payload_type(attr: &SdpAttribute) -> Option<SdpAttributePayloadType> {
+    match *attr {
+        SdpAttribute::Fmtp(ref fmtp) => Some(fmtp.payload_type.clone()),
+        SdpAttribute::Rtcpfb(ref rtcpfb) => Some(rtcpfb.payload_type.clone()),
+        _ => None,
+    }
+}
+
+/// Serializes the attributes of a media section in the order browsers emit
+/// them: direction, mid, the remaining attributes, every rtpmap followed by
+/// its rtcp-fb and fmtp attributes, and finally the candidates.
+pub fn media_attributes_to_sdp(attrs: &[SdpAttribute]) -> String {
+    media_attributes_to_sdp_with_pt_order(attrs, &[])
+}
+
+/// Serializes the attributes like media_attributes_to_sdp, but emits the
+/// codecs in the order of the given payload type preference list. Codecs
+/// which are not part of the list follow in their original order.
+pub fn media_attributes_to_sdp_with_pt_order(attrs: &[SdpAttribute], pt_order: &[u8]) -> String {
+    let is_direction = |attr: &SdpAttribute| {
+        matches!(
+            *attr,
+            SdpAttribute::Sendrecv
+                | SdpAttribute::Sendonly
+                | SdpAttribute::Recvonly
+                | SdpAttribute::Inactive
+        )
+    };
+    let is_mid = |attr: &SdpAttribute| matches!(*attr, SdpAttribute::Mid(_));
+    let is_candidate = |attr: &SdpAttribute| {
+        matches!(
+            *attr,
+            SdpAttribute::Candidate(_) | SdpAttribute::EndOfCandidates
+        )
+    };
+    let is_codec = |attr: &SdpAttribute| {
+        matches!(*attr, SdpAttribute::Rtpmap(_)) || codec_payload_type(attr).is_some()
+    };
+
+    let mut ordered: Vec<&SdpAttribute> = Vec::with_capacity(attrs.len());
+    ordered.extend(attrs.iter().filter(|a| is_direction(a)));
+    ordered.extend(attrs.iter().filter(|a| is_mid(a)));
+    ordered.extend(
+        attrs
+            .iter()
+            .filter(|a| !is_direction(a) && !is_mid(a) && !is_candidate(a) && !is_codec(a)),
+    );
+    let mut rtpmaps: Vec<&SdpAttribute> = attrs
+        .iter()
+        .filter(|a| matches!(**a, SdpAttribute::Rtpmap(_)))
+        .collect();
+    rtpmaps.sort_by_key(|a| match **a {
+        SdpAttribute::Rtpmap(ref rtpmap) => pt_order
+            .iter()
+            .position(|pt| *pt == rtpmap.payload_type)
+            .unwrap_or(pt_order.len()),
+        _ => pt_order.len(),
+    });
+    let mut mapped = BTreeSet::new();
+    for attr in rtpmaps {
+        if let SdpAttribute::Rtpmap(ref rtpmap) = *attr {
+            ordered.push(attr);
+            // A second rtpmap for the same payload type must not repeat the
+            // rtcp-fb and fmtp attributes.
+            if !mapped.insert(rtpmap.payload_type) {
+                continue;
+            }
+            let pt = SdpAttributePayloadType::PayloadType(rtpmap.payload_type);
+            ordered.extend(
+                attrs
+                    .iter()
+                    .filter(|a| matches!(**a, SdpAttribute::Rtcpfb(_)))
+                    .filter(|a| codec_payload_type(a).as_ref() == Some(&pt)),
+            );
+            ordered.extend(
+                attrs
+                    .iter()
+                    .filter(|a| matches!(**a, SdpAttribute::Fmtp(_)))
+                    .filter(|a| codec_payload_type(a).as_ref() == Some(&pt)),
+            );
+        }
+    }
+    ordered.extend(attrs.iter().filter(|a| match codec_payload_type(a) {
+        Some(SdpAttributePayloadType::PayloadType(pt)) => !mapped.contains(&pt),
+        Some(SdpAttributePayloadType::Wildcard) => true,
+        None => false,
+    }));
+    ordered.extend(
+        attrs
+            .iter()
+            .filter(|a| matches!(**a, SdpAttribute::Candidate(_))),
+    );
+    ordered.extend(
+        attrs
+            .iter()
+            .filter(|a| matches!(**a, SdpAttribute::EndOfCandidates)),
+    );
+
+    ordered
+        .iter()
+        .map(|attr| format!("a={}\r\n", attr))
+        .collect()
+}
+
+/// Attribute names are matched case-insensitively, so "RTCP-MUX" and
+/// "rtcp-mux" produce equal attributes. Attribute values keep their case.
 pub fn parse_attribute(value: &str) -> Result<SdpType, SdpParserInternalError> {
-    Ok(SdpType::Attribute(value.trim().parse()?))
+    parse_attribute_with_config(value, &SdpParserConfig::default())
+}
+
+pub fn parse_attribute_with_config(
+    value: &str,
+    config: &SdpParserConfig,
+) -> Result<SdpType, SdpParserInternalError> {
+    Ok(SdpType::Attribute(parse_attribute_value(
+        value.trim(),
+        config,
+    )?))
+}
+
+/// Parses attribute values one after another, keeping the buffers for the
+/// lower-cased attribute name and the token offsets of candidate lines
+/// between calls instead of allocating them for every line. The values of
+/// the parsed attributes are still allocated as usual.
+#[derive(Default)]
+pub struct SdpAttributeParser {
+    config: SdpParserConfig,
+    buffers: ParseBuffers,
+}
+
+impl SdpAttributeParser {
+    pub fn new(config: SdpParserConfig) -> Self {
+        SdpAttributeParser {
+            config,
+            buffers: ParseBuffers::default(),
+        }
+    }
+
+    /// Parses an attribute value without the "a=" prefix, like
+    /// parse_attribute_with_config.
+    pub fn parse(&mut self, value: &str) -> Result<SdpAttribute, SdpParserInternalError> {
+        parse_attribute_value_with_buffers(value.trim(), &self.config, &mut self.buffers)
+    }
+
+    /// Like parse, but stores the result in the given attribute. On error
+    /// the attribute is left untouched.
+    pub fn parse_into(
+        &mut self,
+        value: &str,
+        attribute: &mut SdpAttribute,
+    ) -> Result<(), SdpParserInternalError> {
+        *attribute = self.parse(value)?;
+        Ok(())
+    }
+}
+
+/// Default upper bound for the length of a single line buffered by the
+/// SdpAttributeLineReader.
+pub const DEFAULT_MAX_ATTRIBUTE_LINE_LENGTH: usize = 64 * 1024;
+
+/// Parses attribute lines out of SDP arriving in arbitrary byte chunks, for
+/// example straight from a socket. Partial lines are buffered until their
+/// terminating CRLF or bare LF shows up. Lines may carry the "a=" prefix;
+/// empty lines are skipped. Lines longer than the maximum line length are
+/// reported as an error and dropped instead of being buffered.
+pub struct SdpAttributeLineReader {
+    parser: SdpAttributeParser,
+    buffer: Vec<u8>,
+    // Number of buffered bytes already known not to contain a line feed
+    scanned: usize,
+    max_line_length: usize,
+    // Set while the rest of an over-long line is being skipped
+    discarding: bool,
+}
+
+impl SdpAttributeLineReader {
+    pub fn new(config: SdpParserConfig) -> Self {
+        SdpAttributeLineReader {
+            parser: SdpAttributeParser::new(config),
+            buffer: Vec::new(),
+            scanned: 0,
+            max_line_length: DEFAULT_MAX_ATTRIBUTE_LINE_LENGTH,
+            discarding: false,
+        }
+    }
+
+    pub fn with_max_line_length(mut self, max_line_length: usize) -> Self {
+        self.max_line_length = max_line_length;
+        self
+    }
+
+    /// Appends the chunk and returns the results for all lines which got
+    /// completed by it.
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<Result<SdpAttribute, SdpParserInternalError>> {
+        self.buffer.extend_from_slice(chunk);
+        let mut results = Vec::new();
+        loop {
+            let end = match self.buffer[self.scanned..].iter().position(|&b| b == b'\n') {
+                Some(offset) => self.scanned + offset,
+                None => {
+                    if self.buffer.len() > self.max_line_length {
+                        if !self.discarding {
+                            results.push(Err(self.line_too_long_error()));
+                            self.discarding = true;
+                        }
+                        self.buffer.clear();
+                    }
+                    self.scanned = self.buffer.len();
+                    return results;
+                }
+            };
+            let mut line: Vec<u8> = self.buffer.drain(..=end).collect();
+            self.scanned = 0;
+            line.pop();
+            if line.last() == Some(&b'\r') {
+                line.pop();
+            }
+            if self.discarding {
+                self.discarding = false;
+                continue;
+            }
+            if line.len() > self.max_line_length {
+                results.push(Err(self.line_too_long_error()));
+                continue;
+            }
+            let line = match String::from_utf8(line) {
+                Ok(line) => line,
+                Err(_) => {
+                    results.push(Err(SdpParserInternalError::Invalid(
+                        SdpErrorKind::InvalidLine,
+                        "Attribute line is not valid UTF-8".to_string(),
+                    )));
+                    continue;
+                }
+            };
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let value = line.strip_prefix("a=").unwrap_or(line).trim();
+            results.push(self.parser.parse(value));
+        }
+    }
+
+    /// Returns true if bytes of an incomplete line are buffered.
+    pub fn has_partial_line(&self) -> bool {
+        !self.buffer.is_empty()
+    }
+
+    fn line_too_long_error(&self) -> SdpParserInternalError {
+        SdpParserInternalError::Invalid(
+            SdpErrorKind::InvalidLine,
+            format!(
+                "Attribute line exceeds the maximum length of {} bytes",
+                self.max_line_length
+            ),
+        )
+    }
+}
+
+impl Default for SdpAttributeLineReader {
+    fn default() -> Self {
+        Self::new(SdpParserConfig::default())
+    }
 }
 
 #[cfg(test)]
@@ -4,7 +4,7 @@
 
 extern crate url;
 use super::*;
-use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
 
 macro_rules! make_check_parse {
     ($attr_type:ty, $attr_kind:path) => {
@@ -44,6 +44,13 @@ macro_rules! make_check_parse_and_serialize {
     };
 }
 
+fn strict_config() -> SdpParserConfig {
+    SdpParserConfig {
+        strict: true,
+        ..Default::default()
+    }
+}
+
 #[test]
 fn test_parse_attribute_candidate_and_serialize() {
     let check_parse = make_check_parse!(SdpAttributeCandidate, SdpAttribute::Candidate);
@@ -55,9 +62,15 @@ fn test_parse_attribute_candidate_and_serialize() {
     check_parse_and_serialize("candidate:0 1 TCP 2122252543 172.16.156.106 49760 typ host");
     check_parse_and_serialize("candidate:0 1 TCP 2122252543 ::1 49760 typ host");
     check_parse_and_serialize("candidate:0 1 TCP 2122252543 2001:db8:4860::4444 49760 typ host");
-    check_parse_and_serialize("candidate:0 1 UDP 2122252543 172.16.156.106 49760 typ srflx");
-    check_parse_and_serialize("candidate:0 1 UDP 2122252543 172.16.156.106 49760 typ prflx");
-    check_parse_and_serialize("candidate:0 1 UDP 2122252543 172.16.156.106 49760 typ relay");
+    check_parse_and_serialize(
+        "candidate:0 1 UDP 2122252543 172.16.156.106 49760 typ srflx raddr 192.168.1.4 rport 61665",
+    );
+    check_parse_and_serialize(
+        "candidate:0 1 UDP 2122252543 172.16.156.106 49760 typ prflx raddr 192.168.1.4 rport 61665",
+    );
+    check_parse_and_serialize(
+        "candidate:0 1 UDP 2122252543 172.16.156.106 49760 typ relay raddr 192.168.1.4 rport 61665",
+    );
     check_parse_and_serialize(
         "candidate:0 1 TCP 2122252543 172.16.156.106 49760 typ host tcptype active",
     );
@@ -73,46 +86,815 @@ fn test_parse_attribute_candidate_and_serialize() {
     check_parse_and_serialize(
         "candidate:0 1 TCP 2122252543 172.16.156.106 49760 typ host network-cost 50",
     );
-    check_parse_and_serialize("candidate:1 1 UDP 1685987071 24.23.204.141 54609 typ srflx raddr 192.168.1.4 rport 61665 generation 0");
-    check_parse_and_serialize(
-        "candidate:1 1 UDP 1685987071 24.23.204.141 54609 typ srflx raddr 192.168.1.4 rport 61665",
+    check_parse_and_serialize("candidate:1 1 UDP 1685987071 24.23.204.141 54609 typ srflx raddr 192.168.1.4 rport 61665 generation 0");
+    check_parse_and_serialize(
+        "candidate:1 1 UDP 1685987071 24.23.204.141 54609 typ srflx raddr 192.168.1.4 rport 61665",
+    );
+    check_parse_and_serialize("candidate:1 1 TCP 1685987071 24.23.204.141 54609 typ srflx raddr 192.168.1.4 rport 61665 tcptype passive");
+    check_parse_and_serialize("candidate:1 1 TCP 1685987071 24.23.204.141 54609 typ srflx raddr 192.168.1.4 rport 61665 tcptype passive generation 1");
+    check_parse_and_serialize("candidate:1 1 TCP 1685987071 24.23.204.141 54609 typ srflx raddr 192.168.1.4 rport 61665 tcptype passive generation 1 ufrag +DGd");
+    check_parse_and_serialize("candidate:1 1 TCP 1685987071 24.23.204.141 54609 typ srflx raddr 192.168.1.4 rport 61665 tcptype passive generation 1 ufrag +DGd network-cost 1");
+    check_parse_and_serialize(
+        "candidate:0 1 TCP 2122252543 172.16.156.106 49760 typ host unsupported foo",
+    );
+    check_parse_and_serialize("candidate:0 1 TCP 2122252543 172.16.156.106 49760 typ host unsupported foo more_unsupported bar");
+    check_parse_and_serialize(
+        "candidate:1 1 UDP 1685987071 foo.local 54609 typ srflx raddr bar.local rport 61665",
+    );
+
+    let candidate = check_parse("candidate:1 1 TCP 1685987071 24.23.204.141 54609 typ srflx raddr 192.168.1.4 rport 61665 tcptype passive generation 1 ufrag +DGd network-cost 1 unsupported foo");
+    assert_eq!(candidate.foundation, "1".to_string());
+    assert_eq!(candidate.component, 1);
+    assert_eq!(candidate.transport, SdpAttributeCandidateTransport::Tcp);
+    assert_eq!(candidate.priority, 1_685_987_071);
+    assert_eq!(
+        candidate.address,
+        Address::from_str("24.23.204.141").unwrap()
+    );
+    assert_eq!(candidate.port, 54609);
+    assert_eq!(candidate.c_type, SdpAttributeCandidateType::Srflx);
+    assert_eq!(
+        candidate.raddr,
+        Some(Address::from_str("192.168.1.4").unwrap())
+    );
+    assert_eq!(candidate.rport, Some(61665));
+    assert_eq!(
+        candidate.tcp_type,
+        Some(SdpAttributeCandidateTcpType::Passive)
+    );
+    assert_eq!(candidate.generation, Some(1));
+    assert_eq!(candidate.ufrag, Some("+DGd".to_string()));
+    assert_eq!(candidate.networkcost, Some(1));
+    assert_eq!(
+        candidate.unknown_extensions,
+        vec![("unsupported".to_string(), "foo".to_string())]
+    );
+
+    let candidate = check_parse(
+        "candidate:1 1 UDP 1685987071 24.23.204.141 54609 typ srflx raddr foo.local rport 61665",
+    );
+    assert_eq!(
+        candidate.raddr,
+        Some(Address::Fqdn("foo.local".to_string()))
+    );
+}
+
+#[test]
+fn test_parse_attribute_candidate_generation() {
+    let check_parse = make_check_parse!(SdpAttributeCandidate, SdpAttribute::Candidate);
+
+    let line = "candidate:0 1 UDP 2122252543 172.16.156.106 49760 typ host";
+    assert_eq!(check_parse(line).generation(), None);
+    assert_eq!(
+        check_parse(&format!("{} generation 0", line)).generation(),
+        Some(0)
+    );
+    assert_eq!(
+        check_parse(&format!("{} generation 2 ufrag foo", line)).generation(),
+        Some(2)
+    );
+
+    assert!(parse_attribute(&format!("{} generation two", line)).is_err());
+    assert!(parse_attribute(&format!("{} generation -1", line)).is_err());
+}
+
+#[test]
+fn test_parse_attribute_candidate_network_id_and_cost() {
+    let check_parse = make_check_parse!(SdpAttributeCandidate, SdpAttribute::Candidate);
+    let check_parse_and_serialize =
+        make_check_parse_and_serialize!(check_parse, SdpAttribute::Candidate);
+
+    check_parse_and_serialize(
+        "candidate:0 1 UDP 2122252543 172.16.156.106 49760 typ host generation 0 network-id 2 network-cost 50",
+    );
+
+    let candidate = check_parse(
+        "candidate:0 1 UDP 2122252543 172.16.156.106 49760 typ host network-id 2 network-cost 50",
+    );
+    assert_eq!(candidate.network_id(), Some(2));
+    assert_eq!(candidate.network_cost(), Some(50));
+    assert!(candidate.unknown_extensions.is_empty());
+    assert_eq!(candidate.ufrag(), None);
+
+    let candidate = check_parse(
+        "candidate:0 1 UDP 2122252543 172.16.156.106 49760 typ host generation 0 ufrag 8hhY network-id 1",
+    );
+    assert_eq!(candidate.ufrag(), Some("8hhY"));
+    assert_eq!(candidate.network_id(), Some(1));
+
+    assert!(parse_attribute(
+        "candidate:0 1 UDP 2122252543 172.16.156.106 49760 typ host network-id x"
+    )
+    .is_err());
+    assert!(parse_attribute(
+        "candidate:0 1 UDP 2122252543 172.16.156.106 49760 typ host network-cost 65536"
+    )
+    .is_err());
+}
+
+#[test]
+fn test_parse_attribute_candidate_placeholder() {
+    let check_parse = make_check_parse!(SdpAttributeCandidate, SdpAttribute::Candidate);
+    let check_parse_and_serialize =
+        make_check_parse_and_serialize!(check_parse, SdpAttribute::Candidate);
+
+    check_parse_and_serialize("candidate:0 1 UDP 2122252543 0.0.0.0 0 typ host");
+    assert!(check_parse("candidate:0 1 UDP 2122252543 0.0.0.0 0 typ host").is_placeholder());
+    assert!(check_parse("candidate:0 1 UDP 2122252543 :: 0 typ host").is_placeholder());
+    assert!(!check_parse("candidate:0 1 UDP 2122252543 0.0.0.0 9 typ host").is_placeholder());
+    assert!(
+        !check_parse("candidate:0 1 UDP 2122252543 172.16.156.106 0 typ host").is_placeholder()
+    );
+    assert!(!check_parse("candidate:0 1 UDP 2122252543 foo.local 0 typ host").is_placeholder());
+}
+
+#[test]
+fn test_parse_attribute_candidate_tcptype_so() {
+    let check_parse = make_check_parse!(SdpAttributeCandidate, SdpAttribute::Candidate);
+
+    let candidate =
+        check_parse("candidate:0 1 TCP 2122252543 172.16.156.106 49760 typ host tcptype SO");
+    assert!(candidate.tcp_type == Some(SdpAttributeCandidateTcpType::Simultaneous));
+    assert_eq!(SdpAttributeCandidateTcpType::Simultaneous.to_string(), "so");
+    assert_eq!(
+        SdpAttribute::Candidate(candidate).to_string(),
+        "candidate:0 1 TCP 2122252543 172.16.156.106 49760 typ host tcptype so"
+    );
+    assert!(parse_attribute(
+        "candidate:0 1 TCP 2122252543 172.16.156.106 49760 typ host tcptype simultaneous"
+    )
+    .is_err());
+}
+
+#[test]
+fn test_parse_attribute_candidate_type_case() {
+    let check_parse = make_check_parse!(SdpAttributeCandidate, SdpAttribute::Candidate);
+
+    let host = check_parse("candidate:0 1 UDP 2122252543 172.16.156.106 49760 typ Host");
+    assert!(host.c_type == SdpAttributeCandidateType::Host);
+    let srflx = check_parse(
+        "candidate:1 1 UDP 1685987071 24.23.204.141 54609 typ Srflx raddr 192.168.1.4 rport 61665",
+    );
+    assert!(srflx.c_type == SdpAttributeCandidateType::Srflx);
+    let relay = check_parse(
+        "candidate:2 1 UDP 92217087 10.0.0.1 50000 typ RELAY raddr 24.23.204.141 rport 54609",
+    );
+    assert!(relay.c_type == SdpAttributeCandidateType::Relay);
+    assert_eq!(
+        SdpAttribute::Candidate(host).to_string(),
+        "candidate:0 1 UDP 2122252543 172.16.156.106 49760 typ host"
+    );
+
+    assert!(
+        parse_attribute("candidate:0 1 UDP 2122252543 172.16.156.106 49760 typ hostx").is_err()
+    );
+    assert!(
+        parse_attribute("candidate:0 1 UDP 2122252543 172.16.156.106 49760 typ local").is_err()
+    );
+}
+
+#[test]
+fn test_candidate_related_address_serialization() {
+    let check_parse = make_check_parse!(SdpAttributeCandidate, SdpAttribute::Candidate);
+
+    let mut host = check_parse("candidate:0 1 UDP 2122252543 172.16.156.106 49760 typ host");
+    host.raddr = Some(Address::from_str("192.168.1.4").unwrap());
+    host.rport = Some(61665);
+    assert_eq!(
+        host.to_string(),
+        "0 1 UDP 2122252543 172.16.156.106 49760 typ host"
+    );
+    // The parser rejects such host candidates, so serializing a parsed
+    // candidate never drops a related address.
+    assert!(parse_attribute(
+        "candidate:0 1 UDP 2122252543 172.16.156.106 49760 typ host raddr 0.0.0.0 rport 0"
+    )
+    .is_err());
+
+    let srflx = check_parse(
+        "candidate:1 1 UDP 1685987071 24.23.204.141 54609 typ srflx raddr 192.168.1.4 rport 61665",
+    );
+    assert_eq!(
+        srflx.to_string(),
+        "1 1 UDP 1685987071 24.23.204.141 54609 typ srflx raddr 192.168.1.4 rport 61665"
+    );
+}
+
+#[test]
+fn test_parse_attribute_candidate_unknown_transport() {
+    let line = "candidate:0 1 DCCP 2122252543 172.16.156.106 49760 typ host";
+    let lenient = SdpParserConfig::default();
+    let strict = strict_config();
+
+    match parse_attribute_with_config(line, &lenient) {
+        Ok(SdpType::Attribute(SdpAttribute::Candidate(candidate))) => {
+            assert_eq!(
+                candidate.transport,
+                SdpAttributeCandidateTransport::Unknown("DCCP".to_string())
+            );
+            assert_eq!(
+                candidate.to_string(),
+                "0 1 DCCP 2122252543 172.16.156.106 49760 typ host"
+            );
+        }
+        _ => unreachable!(),
+    }
+    assert!(parse_attribute_with_config(line, &strict).is_err());
+    assert!(parse_attribute_with_config(
+        "candidate:0 1 RTP/AVP 2122252543 172.16.156.106 49760 typ host",
+        &lenient
+    )
+    .is_err());
+}
+
+#[test]
+fn test_candidate_to_ice_candidate() {
+    let check_parse = make_check_parse!(SdpAttributeCandidate, SdpAttribute::Candidate);
+
+    let srflx = check_parse(
+        "candidate:1 1 UDP 1685987071 24.23.204.141 54609 typ srflx raddr 192.168.1.4 rport 61665",
+    );
+    assert_eq!(
+        srflx.to_ice_candidate(),
+        IceCandidate {
+            foundation: "1".to_string(),
+            component: 1,
+            protocol: "udp".to_string(),
+            priority: 1_685_987_071,
+            address: "24.23.204.141".to_string(),
+            port: 54609,
+            typ: "srflx".to_string(),
+            related: Some(("192.168.1.4".to_string(), 61665)),
+            tcp_type: None,
+        }
+    );
+
+    let host = check_parse("candidate:0 1 TCP 2122252543 foo.local 9 typ host tcptype active");
+    let ice = host.to_ice_candidate();
+    assert_eq!(ice.protocol, "tcp");
+    assert_eq!(ice.address, "foo.local");
+    assert_eq!(ice.typ, "host");
+    assert_eq!(ice.related, None);
+    assert_eq!(ice.tcp_type, Some("active".to_string()));
+}
+
+#[test]
+fn test_candidate_is_redundant_with() {
+    let check_parse = make_check_parse!(SdpAttributeCandidate, SdpAttribute::Candidate);
+
+    let candidate = check_parse("candidate:0 1 UDP 2122252543 172.16.156.106 49760 typ host");
+    let lower_priority = check_parse("candidate:0 1 UDP 2122252542 172.16.156.106 49760 typ host");
+    assert!(candidate != lower_priority);
+    assert!(candidate.is_redundant_with(&lower_priority));
+    assert!(lower_priority.is_redundant_with(&candidate));
+
+    for other in &[
+        "candidate:1 1 UDP 2122252543 172.16.156.106 49760 typ host",
+        "candidate:0 2 UDP 2122252543 172.16.156.106 49760 typ host",
+        "candidate:0 1 TCP 2122252543 172.16.156.106 49760 typ host tcptype active",
+        "candidate:0 1 UDP 2122252543 172.16.156.107 49760 typ host",
+        "candidate:0 1 UDP 2122252543 172.16.156.106 49761 typ host",
+    ] {
+        assert!(!candidate.is_redundant_with(&check_parse(other)));
+    }
+}
+
+#[test]
+fn test_candidate_set_dedup() {
+    let check_parse = make_check_parse!(SdpAttributeCandidate, SdpAttribute::Candidate);
+    let line =
+        "candidate:1 1 UDP 1685987071 24.23.204.141 54609 typ srflx raddr 192.168.1.4 rport 61665";
+
+    let mut candidates = std::collections::HashSet::new();
+    assert!(candidates.insert(check_parse(line)));
+    assert!(!candidates.insert(check_parse(line)));
+    assert_eq!(candidates.len(), 1);
+    candidates.insert(check_parse(
+        "candidate:0 1 UDP 2122252543 172.16.156.106 49760 typ host",
+    ));
+    assert_eq!(candidates.len(), 2);
+
+    let mut fqdn_candidates = std::collections::HashSet::new();
+    fqdn_candidates.insert(check_parse(
+        "candidate:0 1 UDP 2122252543 foo.local 49760 typ host",
+    ));
+    fqdn_candidates.insert(check_parse(
+        "candidate:0 1 UDP 2122252543 FOO.local 49760 typ host",
+    ));
+    assert_eq!(fqdn_candidates.len(), 1);
+}
+
+#[test]
+fn test_parse_attribute_candidate_foundation() {
+    let check_parse = make_check_parse!(SdpAttributeCandidate, SdpAttribute::Candidate);
+
+    let candidate = check_parse("candidate:a+B/9 1 UDP 2122252543 172.16.156.106 49760 typ host");
+    assert_eq!(candidate.foundation, "a+B/9");
+    check_parse(&format!(
+        "candidate:{} 1 UDP 2122252543 172.16.156.106 49760 typ host",
+        "f".repeat(32)
+    ));
+
+    // A missing foundation shifts all tokens and leaves one token short
+    match parse_attribute("candidate: 1 UDP 2122252543 172.16.156.106 49760 typ host") {
+        Err(e) => assert_eq!(e.kind(), SdpErrorKind::MissingToken),
+        Ok(_) => unreachable!(),
+    }
+    for foundation in &["f".repeat(33), "foo-bar".to_string()] {
+        match parse_attribute(&format!(
+            "candidate:{} 1 UDP 2122252543 172.16.156.106 49760 typ host",
+            foundation
+        )) {
+            Err(e) => {
+                assert_eq!(e.kind(), SdpErrorKind::InvalidValue);
+                assert!(e.to_string().contains("foundation"));
+            }
+            Ok(_) => unreachable!(),
+        }
+    }
+}
+
+#[test]
+fn test_parse_attribute_candidate_whitespace() {
+    let check_parse = make_check_parse!(SdpAttributeCandidate, SdpAttribute::Candidate);
+
+    let expected = check_parse("candidate:0 1 UDP 2122252543 172.16.156.106 49760 typ host");
+    let padded = check_parse("candidate:  0 1 UDP 2122252543 172.16.156.106 49760 typ host ");
+    assert!(padded == expected);
+    let doubled = check_parse("candidate:0  1 UDP  2122252543 172.16.156.106\t49760   typ  host");
+    assert!(doubled == expected);
+    assert_eq!(
+        SdpAttribute::Candidate(doubled).to_string(),
+        "candidate:0 1 UDP 2122252543 172.16.156.106 49760 typ host"
+    );
+}
+
+#[test]
+fn test_parse_attribute_candidate_compound_transport() {
+    for transport in &["UDP/DTLS", "UDP/TLS/RTP/SAVPF", "DTLS/SCTP"] {
+        let line = format!(
+            "candidate:0 1 {} 2122252543 172.16.156.106 49760 typ host",
+            transport
+        );
+        match SdpAttribute::from_str(&line) {
+            Err(e) => {
+                assert_eq!(e.kind(), SdpErrorKind::UnknownTransport);
+                assert!(e.to_string().contains(transport));
+                assert!(e.to_string().contains("media protocol"));
+            }
+            Ok(_) => unreachable!(),
+        }
+    }
+    match SdpAttributeCandidateTransport::from_str("DCCP") {
+        Err(e) => assert_eq!(
+            e.to_string(),
+            "Parsing error: Unknown candidate transport value 'DCCP'"
+        ),
+        Ok(_) => unreachable!(),
+    }
+}
+
+#[test]
+fn test_parse_attribute_candidate_transport_case() {
+    let check_parse = make_check_parse!(SdpAttributeCandidate, SdpAttribute::Candidate);
+    for (line, transport) in &[
+        (
+            "candidate:0 1 udp 2122252543 172.16.156.106 49760 typ host",
+            "UDP",
+        ),
+        (
+            "candidate:0 1 Udp 2122252543 172.16.156.106 49760 typ host",
+            "UDP",
+        ),
+        (
+            "candidate:0 1 tcp 2122252543 172.16.156.106 49760 typ host",
+            "TCP",
+        ),
+    ] {
+        let candidate = check_parse(line);
+        assert_eq!(candidate.transport.to_string(), *transport);
+        let serialized = SdpAttribute::Candidate(candidate).to_string();
+        assert_eq!(
+            serialized,
+            format!(
+                "candidate:0 1 {} 2122252543 172.16.156.106 49760 typ host",
+                transport
+            )
+        );
+        assert_eq!(check_parse(&serialized).transport.to_string(), *transport);
+    }
+}
+
+#[test]
+fn test_compute_candidate_priority() {
+    assert_eq!(
+        compute_candidate_priority(126, 65535, 1),
+        Some(2_130_706_431)
+    );
+    assert_eq!(
+        compute_candidate_priority(126, 32512, 1),
+        Some(2_122_252_543)
+    );
+    assert_eq!(
+        compute_candidate_priority(100, 65535, 2),
+        Some(1_694_498_814)
+    );
+    assert_eq!(compute_candidate_priority(0, 0, 255), Some(1));
+    assert_eq!(compute_candidate_priority(126, 65535, 0), None);
+
+    assert_eq!(priority_components(2_122_252_543), Some((126, 32512, 1)));
+    for &(type_pref, local_pref, component) in &[(126, 65535, 1), (110, 0, 2), (0, 1, 255)] {
+        let priority = compute_candidate_priority(type_pref, local_pref, component).unwrap();
+        assert_eq!(
+            priority_components(priority),
+            Some((type_pref, local_pref, component))
+        );
+    }
+    assert_eq!(priority_components(256), None);
+}
+
+#[test]
+fn test_parse_attribute_candidate_priority() {
+    let check_parse = make_check_parse!(SdpAttributeCandidate, SdpAttribute::Candidate);
+    let candidate = check_parse("candidate:0 1 UDP 2130706431 172.16.156.106 49760 typ host");
+    assert_eq!(candidate.priority, 2_130_706_431);
+    let candidate = check_parse("candidate:0 1 UDP 4294967295 172.16.156.106 49760 typ host");
+    assert_eq!(candidate.priority, u32::MAX);
+    let candidate = check_parse("candidate:0 1 UDP 0 172.16.156.106 49760 typ host");
+    assert_eq!(candidate.priority, 0);
+
+    assert!(parse_attribute("candidate:0 1 UDP 4294967296 172.16.156.106 49760 typ host").is_err());
+    assert!(parse_attribute("candidate:0 1 UDP 9999999999 172.16.156.106 49760 typ host").is_err());
+    assert!(parse_attribute("candidate:0 1 UDP -1 172.16.156.106 49760 typ host").is_err());
+}
+
+#[test]
+fn test_parse_attribute_candidate_malformed_numbers() {
+    for (line, message) in &[
+        (
+            "candidate:0 1 UDP 21_22 172.16.156.106 49760 typ host",
+            "ICE candidate priority must be a non-negative integer, found '21_22'",
+        ),
+        (
+            "candidate:0 1 UDP -1 172.16.156.106 49760 typ host",
+            "ICE candidate priority must be a non-negative integer, found '-1'",
+        ),
+        (
+            "candidate:0 1 UDP 1e9 172.16.156.106 49760 typ host",
+            "ICE candidate priority must be a non-negative integer, found '1e9'",
+        ),
+        (
+            "candidate:0 1 UDP 2.1e9 172.16.156.106 49760 typ host",
+            "ICE candidate priority must be a non-negative integer, found '2.1e9'",
+        ),
+        (
+            "candidate:0 1_0 UDP 2122252543 172.16.156.106 49760 typ host",
+            "ICE candidate component must be a non-negative integer, found '1_0'",
+        ),
+    ] {
+        match parse_attribute(line) {
+            Err(e) => {
+                assert_eq!(e.kind(), SdpErrorKind::InvalidNumber);
+                assert_eq!(e.to_string(), format!("Parsing error: {}", message));
+            }
+            Ok(_) => unreachable!(),
+        }
+    }
+}
+
+#[test]
+fn test_parse_attribute_candidate_extension_without_value() {
+    for (line, name) in &[
+        (
+            "candidate:0 1 TCP 2122252543 172.16.156.106 49760 typ host tcptype",
+            "tcptype",
+        ),
+        (
+            "candidate:0 1 UDP 2122252543 172.16.156.106 49760 typ host generation 0 ufrag",
+            "ufrag",
+        ),
+        (
+            "candidate:0 1 UDP 2122252543 172.16.156.106 49760 typ host x-unknown",
+            "x-unknown",
+        ),
+    ] {
+        match parse_attribute(line) {
+            Err(e) => {
+                assert_eq!(e.kind(), SdpErrorKind::MissingToken);
+                assert_eq!(
+                    e.to_string(),
+                    format!(
+                        "Parsing error: Ice candidate extension {} is missing its value",
+                        name
+                    )
+                );
+            }
+            Ok(_) => unreachable!(),
+        }
+    }
+}
+
+#[test]
+fn test_parse_attribute_candidate_error_kind() {
+    let kind_of = |value: &str| parse_attribute(value).err().unwrap().kind();
+    let strict = strict_config();
+    assert_eq!(
+        parse_attribute_with_config(
+            "candidate:0 1 FOO 2122252543 172.16.156.106 49760 typ host",
+            &strict
+        )
+        .err()
+        .unwrap()
+        .kind(),
+        SdpErrorKind::UnknownTransport
+    );
+    assert_eq!(
+        kind_of("candidate:0 1 UDP 2122252543 172.16.156.106 70000 typ host"),
+        SdpErrorKind::PortOutOfRange
+    );
+    assert_eq!(
+        kind_of("candidate:0 1 UDP 2122252543 172.16.156.106 49760 typ"),
+        SdpErrorKind::MissingToken
+    );
+    assert_eq!(
+        kind_of("candidate:0 1 UDP 2122252543 172.16.156.106 49760 typ fost"),
+        SdpErrorKind::InvalidValue
+    );
+    assert_eq!(
+        kind_of("candidate:0 1 UDP 2122252543 172.16.156.106 49760 type host"),
+        SdpErrorKind::UnexpectedToken
+    );
+    assert_eq!(
+        kind_of(
+            "fingerprint:sha-1 CD:34:D1:62:16:95:7B:B7:EB:74:E2:39:27:97:EB:0B:23:73:AC:BC extra"
+        ),
+        SdpErrorKind::TokenCount
+    );
+    assert_eq!(kind_of("setup:foobar"), SdpErrorKind::InvalidValue);
+}
+
+#[test]
+fn test_filter_muxed_candidates() {
+    let check_parse = make_check_parse!(SdpAttributeCandidate, SdpAttribute::Candidate);
+    let candidates = vec![
+        check_parse("candidate:0 1 UDP 2122252543 172.16.156.106 49760 typ host"),
+        check_parse("candidate:0 2 UDP 2122252542 172.16.156.106 49761 typ host"),
+    ];
+
+    let muxed = filter_muxed_candidates(&candidates, true);
+    assert_eq!(muxed.len(), 1);
+    assert_eq!(muxed[0].component, 1);
+    assert_eq!(muxed[0].port, 49760);
+
+    assert_eq!(filter_muxed_candidates(&candidates, false).len(), 2);
+}
+
+#[test]
+fn test_candidate_socket_addr() {
+    let check_parse = make_check_parse!(SdpAttributeCandidate, SdpAttribute::Candidate);
+
+    let candidate = check_parse(
+        "candidate:1 1 UDP 1685987071 24.23.204.141 54609 typ srflx raddr 192.168.1.4 rport 61665",
+    );
+    assert_eq!(
+        candidate.socket_addr(),
+        Some(SocketAddr::new(
+            IpAddr::V4(Ipv4Addr::new(24, 23, 204, 141)),
+            54609
+        ))
+    );
+    assert_eq!(
+        candidate.remote_socket_addr(),
+        Some(SocketAddr::new(
+            IpAddr::V4(Ipv4Addr::new(192, 168, 1, 4)),
+            61665
+        ))
+    );
+
+    let candidate = check_parse("candidate:0 1 UDP 2122252543 ::1 49760 typ host");
+    assert_eq!(
+        candidate.socket_addr(),
+        Some(SocketAddr::new(IpAddr::V6(Ipv6Addr::LOCALHOST), 49760))
+    );
+    assert_eq!(candidate.remote_socket_addr(), None);
+
+    let candidate = check_parse("candidate:0 1 UDP 2122252543 foo.local 49760 typ host");
+    assert_eq!(candidate.socket_addr(), None);
+}
+
+#[test]
+fn test_parse_attribute_candidate_unknown_extension_strict() {
+    let line = "candidate:0 1 UDP 2122252543 172.16.156.106 49760 typ host unsupported foo";
+    let strict = strict_config();
+
+    match parse_attribute(line) {
+        Ok(SdpType::Attribute(SdpAttribute::Candidate(candidate))) => assert_eq!(
+            candidate.unknown_extensions,
+            vec![("unsupported".to_string(), "foo".to_string())]
+        ),
+        _ => unreachable!(),
+    }
+    assert!(matches!(
+        parse_attribute_with_config(line, &strict),
+        Err(SdpParserInternalError::Unsupported(_))
+    ));
+    assert!(parse_attribute_with_config(
+        "candidate:0 1 UDP 2122252543 172.16.156.106 49760 typ host generation 0",
+        &strict
+    )
+    .is_ok());
+}
+
+#[test]
+fn test_parse_attribute_candidate_ipv6_uppercase() {
+    let check_parse = make_check_parse!(SdpAttributeCandidate, SdpAttribute::Candidate);
+
+    let upper = check_parse("candidate:0 1 UDP 2122252543 2001:DB8:0::1 49760 typ host");
+    let lower = check_parse("candidate:0 1 UDP 2122252543 2001:db8::1 49760 typ host");
+    assert_eq!(
+        upper.address,
+        Address::Ip(IpAddr::V6(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1)))
+    );
+    assert!(upper == lower);
+    assert_eq!(
+        SdpAttribute::Candidate(upper).to_string(),
+        "candidate:0 1 UDP 2122252543 2001:db8::1 49760 typ host"
+    );
+}
+
+#[test]
+fn test_parse_attribute_candidate_ipv4_compatible_ipv6() {
+    let check_parse = make_check_parse!(SdpAttributeCandidate, SdpAttribute::Candidate);
+
+    let candidate = check_parse("candidate:0 1 UDP 2122252543 ::127.0.0.1 49760 typ host");
+    assert_eq!(
+        candidate.address,
+        Address::Ip(IpAddr::V6(Ipv4Addr::new(127, 0, 0, 1).to_ipv6_compatible()))
+    );
+    let mapped = check_parse("candidate:0 1 UDP 2122252543 ::ffff:127.0.0.1 49760 typ host");
+    assert_eq!(
+        mapped.address,
+        Address::Ip(IpAddr::V6(Ipv4Addr::new(127, 0, 0, 1).to_ipv6_mapped()))
+    );
+    assert_eq!(
+        SdpAttribute::Candidate(mapped).to_string(),
+        "candidate:0 1 UDP 2122252543 ::ffff:127.0.0.1 49760 typ host"
+    );
+}
+
+#[test]
+fn test_parse_attribute_candidate_bracketed_ipv6() {
+    let check_parse = make_check_parse!(SdpAttributeCandidate, SdpAttribute::Candidate);
+    let strict = strict_config();
+
+    let bare = check_parse("candidate:0 1 UDP 2122252543 ::1 49760 typ host");
+    let bracketed = check_parse("candidate:0 1 UDP 2122252543 [::1] 49760 typ host");
+    assert_eq!(
+        bracketed.address,
+        Address::Ip(IpAddr::V6(Ipv6Addr::LOCALHOST))
+    );
+    assert!(bracketed == bare);
+    assert_eq!(
+        SdpAttribute::Candidate(bracketed).to_string(),
+        "candidate:0 1 UDP 2122252543 ::1 49760 typ host"
+    );
+
+    match parse_attribute_with_config("candidate:0 1 UDP 2122252543 [::1] 49760 typ host", &strict)
+    {
+        Err(e) => assert_eq!(e.kind(), SdpErrorKind::InvalidAddress),
+        Ok(_) => unreachable!(),
+    }
+    assert!(parse_attribute_with_config(
+        "candidate:0 1 UDP 2122252543 ::1 49760 typ host",
+        &strict
+    )
+    .is_ok());
+    assert!(
+        parse_attribute("candidate:0 1 UDP 2122252543 [172.16.156.106] 49760 typ host").is_err()
+    );
+    assert!(parse_attribute("candidate:0 1 UDP 2122252543 [::1 49760 typ host").is_err());
+}
+
+#[test]
+fn test_parse_attribute_candidate_ipv6_scope() {
+    let check_parse = make_check_parse!(SdpAttributeCandidate, SdpAttribute::Candidate);
+    let check_parse_and_serialize =
+        make_check_parse_and_serialize!(check_parse, SdpAttribute::Candidate);
+
+    check_parse_and_serialize("candidate:0 1 UDP 2122252543 fe80::1%eth0 49760 typ host");
+
+    let candidate = check_parse("candidate:0 1 UDP 2122252543 fe80::1%eth0 49760 typ host");
+    assert_eq!(
+        candidate.address,
+        Address::Ip(IpAddr::V6(Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 1)))
+    );
+    assert_eq!(candidate.scope, Some("eth0".to_string()));
+    assert_eq!(
+        check_parse("candidate:0 1 UDP 2122252543 fe80::1 49760 typ host").scope,
+        None
+    );
+
+    assert!(parse_attribute("candidate:0 1 UDP 2122252543 fe80::1% 49760 typ host").is_err());
+    assert!(
+        parse_attribute("candidate:0 1 UDP 2122252543 172.16.156.106%eth0 49760 typ host").is_err()
+    );
+    assert!(parse_attribute("candidate:0 1 UDP 2122252543 foo.local%eth0 49760 typ host").is_err());
+}
+
+#[test]
+fn test_parse_attribute_candidate_unspecified_related_address() {
+    let check_parse = make_check_parse!(SdpAttributeCandidate, SdpAttribute::Candidate);
+    let check_parse_and_serialize =
+        make_check_parse_and_serialize!(check_parse, SdpAttribute::Candidate);
+    let strict = strict_config();
+
+    for line in &[
+        "candidate:0 1 UDP 41885439 172.16.156.106 49760 typ relay raddr 0.0.0.0 rport 0",
+        "candidate:0 1 UDP 41885439 2001:db8::1 49760 typ relay raddr :: rport 0",
+    ] {
+        check_parse_and_serialize(line);
+        assert!(check_parse(line).has_unspecified_related_address());
+        assert!(parse_attribute_with_config(line, &strict).is_ok());
+    }
+
+    assert!(!check_parse(
+        "candidate:0 1 UDP 41885439 172.16.156.106 49760 typ relay raddr 192.168.1.4 rport 61665"
+    )
+    .has_unspecified_related_address());
+    assert!(
+        !check_parse("candidate:0 1 UDP 2122252543 0.0.0.0 49760 typ host")
+            .has_unspecified_related_address()
+    );
+}
+
+#[test]
+fn test_parse_attribute_candidate_related_address() {
+    let strict = strict_config();
+    let error_of = |line: &str| parse_attribute(line).err().unwrap();
+
+    assert!(parse_attribute("candidate:0 1 UDP 2122252543 172.16.156.106 49760 typ host").is_ok());
+    assert!(parse_attribute(
+        "candidate:1 1 UDP 1685987071 24.23.204.141 54609 typ srflx raddr 192.168.1.4 rport 61665"
+    )
+    .is_ok());
+    assert!(parse_attribute(
+        "candidate:1 1 UDP 1685987071 24.23.204.141 54609 typ relay raddr 192.168.1.4 rport 61665"
+    )
+    .is_ok());
+
+    let error = error_of(
+        "candidate:0 1 UDP 2122252543 172.16.156.106 49760 typ host raddr 192.168.1.4 rport 61665",
     );
-    check_parse_and_serialize("candidate:1 1 TCP 1685987071 24.23.204.141 54609 typ srflx raddr 192.168.1.4 rport 61665 tcptype passive");
-    check_parse_and_serialize("candidate:1 1 TCP 1685987071 24.23.204.141 54609 typ srflx raddr 192.168.1.4 rport 61665 tcptype passive generation 1");
-    check_parse_and_serialize("candidate:1 1 TCP 1685987071 24.23.204.141 54609 typ srflx raddr 192.168.1.4 rport 61665 tcptype passive generation 1 ufrag +DGd");
-    check_parse_and_serialize("candidate:1 1 TCP 1685987071 24.23.204.141 54609 typ srflx raddr 192.168.1.4 rport 61665 tcptype passive generation 1 ufrag +DGd network-cost 1");
-    check_parse_and_serialize(
-        "candidate:0 1 TCP 2122252543 172.16.156.106 49760 typ host unsupported foo",
+    assert_eq!(error.kind(), SdpErrorKind::UnexpectedToken);
+    assert_eq!(
+        error.to_string(),
+        "Parsing error: ICE host candidates must not have a raddr or rport"
+    );
+    assert!(
+        parse_attribute("candidate:0 1 UDP 2122252543 172.16.156.106 49760 typ host rport 0")
+            .is_err()
     );
-    check_parse_and_serialize("candidate:0 1 TCP 2122252543 172.16.156.106 49760 typ host unsupported foo more_unsupported bar");
 
-    let candidate = check_parse("candidate:1 1 TCP 1685987071 24.23.204.141 54609 typ srflx raddr 192.168.1.4 rport 61665 tcptype passive generation 1 ufrag +DGd network-cost 1 unsupported foo");
-    assert_eq!(candidate.foundation, "1".to_string());
-    assert_eq!(candidate.component, 1);
-    assert_eq!(candidate.transport, SdpAttributeCandidateTransport::Tcp);
-    assert_eq!(candidate.priority, 1_685_987_071);
+    let error = error_of("candidate:1 1 UDP 1685987071 24.23.204.141 54609 typ srflx");
+    assert_eq!(error.kind(), SdpErrorKind::MissingToken);
     assert_eq!(
-        candidate.address,
-        Address::from_str("24.23.204.141").unwrap()
+        error.to_string(),
+        "Parsing error: ICE srflx candidates require a raddr"
     );
-    assert_eq!(candidate.port, 54609);
-    assert_eq!(candidate.c_type, SdpAttributeCandidateType::Srflx);
     assert_eq!(
-        candidate.raddr,
-        Some(Address::from_str("192.168.1.4").unwrap())
+        error_of("candidate:1 1 UDP 1685987071 24.23.204.141 54609 typ prflx").to_string(),
+        "Parsing error: ICE prflx candidates require a raddr"
     );
-    assert_eq!(candidate.rport, Some(61665));
     assert_eq!(
-        candidate.tcp_type,
-        Some(SdpAttributeCandidateTcpType::Passive)
+        error_of("candidate:1 1 UDP 1685987071 24.23.204.141 54609 typ relay raddr 192.168.1.4")
+            .to_string(),
+        "Parsing error: ICE relay candidates require a rport"
     );
-    assert_eq!(candidate.generation, Some(1));
-    assert_eq!(candidate.ufrag, Some("+DGd".to_string()));
-    assert_eq!(candidate.networkcost, Some(1));
+
+    // Strict mode applies the same checks
+    assert!(parse_attribute_with_config(
+        "candidate:1 1 UDP 1685987071 24.23.204.141 54609 typ srflx",
+        &strict
+    )
+    .is_err());
+
+    // Relay candidates without any related address and incomplete related
+    // addresses of the other types are rejected as well
+    let error = error_of("candidate:1 1 UDP 1685987071 24.23.204.141 54609 typ relay");
+    assert_eq!(error.kind(), SdpErrorKind::MissingToken);
     assert_eq!(
-        candidate.unknown_extensions,
-        vec![("unsupported".to_string(), "foo".to_string())]
+        error.to_string(),
+        "Parsing error: ICE relay candidates require a raddr"
+    );
+    assert_eq!(
+        error_of("candidate:1 1 UDP 1685987071 24.23.204.141 54609 typ srflx raddr 192.168.1.4")
+            .to_string(),
+        "Parsing error: ICE srflx candidates require a rport"
+    );
+    assert!(parse_attribute(
+        "candidate:1 1 UDP 1685987071 24.23.204.141 54609 typ prflx rport 61665"
     )
+    .is_err());
 }
 
 #[test]
@@ -120,7 +902,7 @@ fn test_anonymize_attribute_candidate() -> Result<(), SdpParserInternalError> {
     let mut anon = StatefulSdpAnonymizer::new();
     let candidate_1 = parse_attribute("candidate:0 1 TCP 2122252543 ::8 49760 typ host")?;
     let candidate_2 =
-        parse_attribute("candidate:0 1 UDP 2122252543 172.16.156.106 19361 typ srflx")?;
+        parse_attribute("candidate:0 1 UDP 2122252543 172.16.156.106 19361 typ host")?;
     let candidate_3 = parse_attribute("candidate:1 1 TCP 1685987071 24.23.204.141 54609 typ srflx raddr 192.168.1.4 rport 61665 tcptype passive generation 1 ufrag +DGd")?;
     if let SdpType::Attribute(SdpAttribute::Candidate(candidate)) = candidate_1 {
         let masked = candidate.masked_clone(&mut anon);
@@ -156,7 +938,11 @@ fn test_parse_attribute_candidate_errors() {
     assert!(
         parse_attribute("candidate:0 foo UDP 2122252543 172.16.156.106 49760 typ host").is_err()
     );
-    assert!(parse_attribute("candidate:0 1 FOO 2122252543 172.16.156.106 49760 typ host").is_err());
+    assert!(parse_attribute_with_config(
+        "candidate:0 1 FOO 2122252543 172.16.156.106 49760 typ host",
+        &strict_config()
+    )
+    .is_err());
     assert!(parse_attribute("candidate:0 1 UDP foo 172.16.156.106 49760 typ host").is_err());
     assert!(parse_attribute("candidate:0 1 UDP 2122252543 372.16.356 49760 typ host").is_err());
     assert!(parse_attribute("candidate:0 1 UDP 2122252543 172.16.156.106 70000 typ host").is_err());
@@ -191,6 +977,52 @@ fn test_parse_attribute_candidate_errors() {
     .is_err());
 }
 
+#[test]
+fn test_parse_attribute_candidate_hostname_resolver() -> Result<(), SdpParserInternalError> {
+    let candidate_str = "candidate:0 1 UDP 2122252543 foo.local 49760 typ host";
+    let config = SdpParserConfig {
+        hostname_resolver: Some(|name| match name {
+            "foo.local" => Some(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1))),
+            _ => None,
+        }),
+        ..Default::default()
+    };
+
+    if let SdpType::Attribute(SdpAttribute::Candidate(candidate)) =
+        parse_attribute_with_config(candidate_str, &config)?
+    {
+        assert_eq!(candidate.address, Address::Fqdn("foo.local".to_string()));
+        assert_eq!(
+            candidate.resolved_address,
+            Some(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)))
+        );
+        assert_eq!(
+            SdpAttribute::Candidate(candidate).to_string(),
+            candidate_str
+        );
+    } else {
+        unreachable!();
+    }
+
+    if let SdpType::Attribute(SdpAttribute::Candidate(candidate)) = parse_attribute_with_config(
+        "candidate:0 1 UDP 2122252543 bar.local 49760 typ host",
+        &config,
+    )? {
+        assert_eq!(candidate.resolved_address, None);
+    } else {
+        unreachable!();
+    }
+
+    if let SdpType::Attribute(SdpAttribute::Candidate(candidate)) = parse_attribute(candidate_str)?
+    {
+        assert_eq!(candidate.address, Address::Fqdn("foo.local".to_string()));
+        assert_eq!(candidate.resolved_address, None);
+    } else {
+        unreachable!();
+    }
+    Ok(())
+}
+
 #[test]
 fn test_parse_dtls_message() {
     let check_parse = make_check_parse!(SdpAttributeDtlsMessage, SdpAttribute::DtlsMessage);
@@ -242,6 +1074,7 @@ fn test_parse_attribute_extmap() {
 
     check_parse_and_serialize("extmap:1/sendonly urn:ietf:params:rtp-hdrext:ssrc-audio-level");
     check_parse_and_serialize("extmap:2/sendrecv urn:ietf:params:rtp-hdrext:ssrc-audio-level");
+    check_parse_and_serialize("extmap:3/inactive urn:ietf:params:rtp-hdrext:ssrc-audio-level");
     check_parse_and_serialize(
         "extmap:3 http://www.webrtc.org/experiments/rtp-hdrext/abs-send-time",
     );
@@ -264,6 +1097,73 @@ fn test_parse_attribute_extmap() {
     assert!(parse_attribute(&bad_char).is_err());
 }
 
+#[test]
+fn test_attribute_direction_roundtrip() {
+    for direction in &[
+        SdpAttributeDirection::Recvonly,
+        SdpAttributeDirection::Sendonly,
+        SdpAttributeDirection::Sendrecv,
+        SdpAttributeDirection::Inactive,
+    ] {
+        let serialized = direction.to_string();
+        assert!(SdpAttributeDirection::from_str(&serialized).unwrap() == *direction);
+    }
+    assert_eq!(SdpAttributeDirection::Inactive.to_string(), "inactive");
+    assert!(
+        SdpAttributeDirection::from_str("SendOnly").unwrap() == SdpAttributeDirection::Sendonly
+    );
+    assert!(SdpAttributeDirection::from_str("unsupported").is_err());
+    assert!(SdpAttributeDirection::from_str("").is_err());
+}
+
+#[test]
+fn test_parse_attribute_extmap_id_range_and_direction() {
+    let check_parse = make_check_parse!(SdpAttributeExtmap, SdpAttribute::Extmap);
+
+    for id in &[1, 14] {
+        let extmap = check_parse(&format!("extmap:{} urn:example:foo", id));
+        assert!(!extmap.requires_two_byte_header());
+    }
+    for id in &[15, 255] {
+        let extmap = check_parse(&format!("extmap:{}/recvonly urn:example:foo", id));
+        assert!(extmap.requires_two_byte_header());
+        assert!(extmap.effective_direction() == SdpAttributeDirection::Recvonly);
+    }
+
+    let extmap = check_parse("extmap:1 urn:example:foo");
+    assert!(extmap.direction.is_none());
+    assert!(extmap.effective_direction() == SdpAttributeDirection::Sendrecv);
+
+    for id in &[0, 256, 4096] {
+        match parse_attribute(&format!("extmap:{} urn:example:foo", id)) {
+            Err(e) => assert_eq!(e.kind(), SdpErrorKind::InvalidNumber),
+            Ok(_) => unreachable!(),
+        }
+    }
+}
+
+#[test]
+fn test_extmap_header_extension() {
+    let check_parse = make_check_parse!(SdpAttributeExtmap, SdpAttribute::Extmap);
+
+    let extmap = check_parse("extmap:1 urn:ietf:params:rtp-hdrext:ssrc-audio-level");
+    assert!(extmap.header_extension() == SdpRtpHeaderExtension::SsrcAudioLevel);
+    assert_eq!(extmap.url, "urn:ietf:params:rtp-hdrext:ssrc-audio-level");
+    assert_eq!(
+        extmap.header_extension().to_string(),
+        "urn:ietf:params:rtp-hdrext:ssrc-audio-level"
+    );
+
+    let extmap = check_parse("extmap:4 urn:ietf:params:rtp-hdrext:sdes:mid");
+    assert!(extmap.header_extension() == SdpRtpHeaderExtension::SdesMid);
+
+    let extmap = check_parse("extmap:7 urn:example:foo");
+    assert!(
+        extmap.header_extension() == SdpRtpHeaderExtension::Other("urn:example:foo".to_string())
+    );
+    assert_eq!(extmap.header_extension().to_string(), "urn:example:foo");
+}
+
 #[test]
 fn test_parse_attribute_fingerprint() {
     let check_parse = make_check_parse!(SdpAttributeFingerprint, SdpAttribute::Fingerprint);
@@ -337,6 +1237,47 @@ fn test_parse_attribute_fingerprint() {
     .is_err());
 }
 
+#[test]
+fn test_fingerprint_matches_digest() {
+    let check_parse = make_check_parse!(SdpAttributeFingerprint, SdpAttribute::Fingerprint);
+
+    let fingerprint = check_parse(
+        "fingerprint:sha-1 CD:34:D1:62:16:95:7B:B7:EB:74:E2:39:27:97:EB:0B:23:73:AC:BC",
+    );
+    let mut digest = [
+        0xCD, 0x34, 0xD1, 0x62, 0x16, 0x95, 0x7B, 0xB7, 0xEB, 0x74, 0xE2, 0x39, 0x27, 0x97, 0xEB,
+        0x0B, 0x23, 0x73, 0xAC, 0xBC,
+    ];
+    assert!(fingerprint.matches(&digest));
+    assert!(!fingerprint.matches(&digest[..19]));
+    assert!(!fingerprint.matches(&[]));
+    digest[19] = 0xBD;
+    assert!(!fingerprint.matches(&digest));
+}
+
+#[test]
+fn test_parse_attribute_fingerprint_hash_case() {
+    let check_parse = make_check_parse!(SdpAttributeFingerprint, SdpAttribute::Fingerprint);
+
+    let lower = check_parse(
+        "fingerprint:sha-256 CD:34:D1:62:16:95:7B:B7:EB:74:E2:39:27:97:EB:0B:23:73:AC:BC:\
+         27:97:EB:0B:23:73:AC:BC:CD:34:D1:62",
+    );
+    let upper = check_parse(
+        "fingerprint:SHA-256 CD:34:D1:62:16:95:7B:B7:EB:74:E2:39:27:97:EB:0B:23:73:AC:BC:\
+         27:97:EB:0B:23:73:AC:BC:CD:34:D1:62",
+    );
+    assert!(lower.hash_algorithm == SdpAttributeFingerprintHashType::Sha256);
+    assert!(upper.hash_algorithm == SdpAttributeFingerprintHashType::Sha256);
+    assert_eq!(upper.to_string(), lower.to_string());
+    assert!(upper.to_string().starts_with("sha-256 "));
+
+    let sha1 = check_parse(
+        "fingerprint:Sha-1 CD:34:D1:62:16:95:7B:B7:EB:74:E2:39:27:97:EB:0B:23:73:AC:BC",
+    );
+    assert!(sha1.hash_algorithm == SdpAttributeFingerprintHashType::Sha1);
+}
+
 #[test]
 fn test_parse_attribute_fmtp() {
     let check_parse = make_check_parse!(SdpAttributeFmtp, SdpAttribute::Fmtp);
@@ -376,6 +1317,220 @@ fn test_parse_attribute_fmtp() {
     );
 }
 
+#[test]
+fn test_parse_attribute_fmtp_all_parameters() {
+    let check_parse = make_check_parse!(SdpAttributeFmtp, SdpAttribute::Fmtp);
+    let check_parse_and_serialize =
+        make_check_parse_and_serialize!(check_parse, SdpAttribute::Fmtp);
+
+    check_parse_and_serialize("fmtp:109 maxplaybackrate=46000;stereo=1;x-foo=bar");
+
+    let fmtp = check_parse("fmtp:109 maxplaybackrate=46000;stereo=1;x-foo=bar;x-flag");
+    assert_eq!(fmtp.parameters.maxplaybackrate, 46000);
+    assert!(fmtp.parameters.stereo);
+    assert_eq!(fmtp.parameters.unknown_tokens, vec!["x-foo=bar", "x-flag"]);
+
+    let all = fmtp.all_parameters();
+    assert_eq!(all.len(), 4);
+    assert_eq!(all["maxplaybackrate"], Some("46000".to_string()));
+    assert_eq!(all["stereo"], Some("1".to_string()));
+    assert_eq!(all["x-foo"], Some("bar".to_string()));
+    assert_eq!(all["x-flag"], None);
+
+    check_parse_and_serialize("fmtp:96 x-flag");
+    let flag = check_parse("fmtp:96 x-flag");
+    assert!(flag.parameters.dtmf_tones.is_empty());
+    assert_eq!(flag.parameters.unknown_tokens, vec!["x-flag"]);
+    assert_eq!(flag.all_parameters()["x-flag"], None);
+
+    check_parse_and_serialize("fmtp:109 x-flag;y-flag");
+    let flags = check_parse("fmtp:109 x-flag;y-flag");
+    let flags = flags.all_parameters();
+    assert_eq!(flags.len(), 2);
+    assert_eq!(flags["x-flag"], None);
+    assert_eq!(flags["y-flag"], None);
+
+    // Explicitly given default values are kept as well
+    let defaults = check_parse("fmtp:109 usedtx=0;maxplaybackrate=48000;stereo=1");
+    assert_eq!(
+        SdpAttribute::Fmtp(defaults.clone()).to_string(),
+        "fmtp:109 stereo=1"
+    );
+    let defaults = defaults.all_parameters();
+    assert_eq!(defaults.len(), 3);
+    assert_eq!(defaults["usedtx"], Some("0".to_string()));
+    assert_eq!(defaults["maxplaybackrate"], Some("48000".to_string()));
+
+    let dtmf = check_parse("fmtp:101 0-15");
+    assert_eq!(dtmf.all_parameters().len(), 1);
+    assert_eq!(dtmf.all_parameters()["0-15"], None);
+    let red = check_parse("fmtp:100 111/111");
+    assert_eq!(red.all_parameters().len(), 1);
+    assert_eq!(red.all_parameters()["111/111"], None);
+
+    let spaced = check_parse("fmtp:109 useinbandfec=1; x-foo=bar");
+    assert_eq!(spaced.all_parameters()["x-foo"], Some("bar".to_string()));
+}
+
+#[test]
+fn test_parse_attribute_fmtp_duplicate_parameters() {
+    let check_parse = make_check_parse!(SdpAttributeFmtp, SdpAttribute::Fmtp);
+
+    let fmtp = check_parse("fmtp:96 a=1;a=2");
+    assert_eq!(fmtp.parameters.unknown_tokens, vec!["a=1", "a=2"]);
+    assert_eq!(fmtp.all_parameters().len(), 1);
+    assert_eq!(fmtp.all_parameters()["a"], Some("2".to_string()));
+    let serialized = SdpAttribute::Fmtp(fmtp.clone()).to_string();
+    assert!(check_parse(&serialized) == fmtp);
+
+    let fmtp = check_parse("fmtp:109 maxplaybackrate=16000;maxplaybackrate=48000");
+    assert_eq!(fmtp.parameters.maxplaybackrate, 48000);
+    assert_eq!(
+        fmtp.all_parameters()["maxplaybackrate"],
+        Some("48000".to_string())
+    );
+}
+
+#[test]
+fn test_parse_attribute_fmtp_wildcard() {
+    let check_parse = make_check_parse!(SdpAttributeFmtp, SdpAttribute::Fmtp);
+    let check_parse_and_serialize =
+        make_check_parse_and_serialize!(check_parse, SdpAttribute::Fmtp);
+
+    check_parse_and_serialize("fmtp:* x-google-max-bitrate=2000");
+    let fmtp = check_parse("fmtp:* x-google-max-bitrate=2000");
+    assert_eq!(fmtp.payload_type, SdpAttributePayloadType::Wildcard);
+    assert_eq!(
+        fmtp.all_parameters()["x-google-max-bitrate"],
+        Some("2000".to_string())
+    );
+    assert!(check_parse("fmtp:* stereo=1").parameters.stereo);
+
+    assert!(parse_attribute("fmtp:*").is_err());
+    assert!(parse_attribute("fmtp:** stereo=1").is_err());
+}
+
+#[test]
+fn test_parse_attribute_fmtp_redundancy() {
+    let check_parse = make_check_parse!(SdpAttributeFmtp, SdpAttribute::Fmtp);
+    let check_parse_and_serialize =
+        make_check_parse_and_serialize!(check_parse, SdpAttribute::Fmtp);
+
+    check_parse_and_serialize("fmtp:122 100/100");
+    check_parse_and_serialize("fmtp:122 100/100/100");
+    check_parse_and_serialize("fmtp:100 111/111");
+    check_parse_and_serialize("fmtp:101 0-15");
+
+    assert_eq!(
+        check_parse("fmtp:122 100/100/100").parameters.encodings,
+        vec![100, 100, 100]
+    );
+    let red = check_parse("fmtp:100 111/111");
+    assert_eq!(red.parameters.encodings, vec![111, 111]);
+    assert!(red.parameters.unknown_tokens.is_empty());
+    assert_eq!(
+        check_parse("fmtp:101 0-15,66").parameters.dtmf_tones,
+        "0-15,66"
+    );
+
+    assert!(parse_attribute("fmtp:122 100/foo").is_err());
+}
+
+#[test]
+fn test_build_attribute_fmtp() {
+    let parameters = SdpAttributeFmtpParameters {
+        maxaveragebitrate: 32000,
+        stereo: true,
+        useinbandfec: true,
+        ..Default::default()
+    };
+    let fmtp =
+        SdpAttributeFmtp::from_parameters(SdpAttributePayloadType::PayloadType(109), parameters);
+    assert_eq!(
+        *fmtp.payload_type(),
+        SdpAttributePayloadType::PayloadType(109)
+    );
+    assert_eq!(fmtp.parsed_parameters().maxaveragebitrate, 32000);
+    assert_eq!(
+        fmtp.parameters(),
+        ["maxaveragebitrate=32000", "stereo=1", "useinbandfec=1"]
+    );
+
+    let attr = SdpAttribute::Fmtp(fmtp);
+    assert_eq!(
+        attr.to_string(),
+        "fmtp:109 maxaveragebitrate=32000;stereo=1;useinbandfec=1"
+    );
+    match parse_attribute(&attr.to_string()) {
+        Ok(SdpType::Attribute(parsed)) => assert!(parsed == attr),
+        Err(e) => panic!("{}", e),
+        _ => unreachable!(),
+    }
+
+    match parse_attribute("fmtp:101 0-15") {
+        Ok(SdpType::Attribute(SdpAttribute::Fmtp(parsed))) => {
+            assert_eq!(
+                *parsed.payload_type(),
+                SdpAttributePayloadType::PayloadType(101)
+            );
+            assert_eq!(parsed.parameters(), ["0-15"]);
+            assert_eq!(parsed.parsed_parameters().dtmf_tones, "0-15");
+            assert_eq!(parsed.parsed_parameters().maxplaybackrate, 48000);
+        }
+        Err(e) => panic!("{}", e),
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn test_build_attribute_fmtp_from_tokens() -> Result<(), SdpParserInternalError> {
+    let tokens = vec!["useinbandfec=1".to_string(), "x-foo=bar".to_string()];
+    let fmtp = SdpAttributeFmtp::new(SdpAttributePayloadType::PayloadType(109), tokens)?;
+    assert_eq!(fmtp.parameters(), ["useinbandfec=1", "x-foo=bar"]);
+    assert!(fmtp.parsed_parameters().useinbandfec);
+    assert_eq!(fmtp.parsed_parameters().unknown_tokens, vec!["x-foo=bar"]);
+
+    let attr = SdpAttribute::Fmtp(fmtp);
+    assert_eq!(attr.to_string(), "fmtp:109 useinbandfec=1;x-foo=bar");
+    assert!(SdpAttribute::from_str(&attr.to_string())? == attr);
+
+    let dtmf = SdpAttributeFmtp::new(
+        SdpAttributePayloadType::PayloadType(101),
+        vec!["0-15".to_string()],
+    )?;
+    assert_eq!(dtmf.parsed_parameters().dtmf_tones, "0-15");
+
+    assert!(SdpAttributeFmtp::new(SdpAttributePayloadType::PayloadType(109), Vec::new()).is_err());
+    assert!(SdpAttributeFmtp::new(
+        SdpAttributePayloadType::PayloadType(109),
+        vec!["stereo=2".to_string()]
+    )
+    .is_err());
+    Ok(())
+}
+
+#[test]
+fn test_parse_attribute_fmtp_vp8_vp9() {
+    let check_parse = make_check_parse!(SdpAttributeFmtp, SdpAttribute::Fmtp);
+    let check_parse_and_serialize =
+        make_check_parse_and_serialize!(check_parse, SdpAttribute::Fmtp);
+
+    check_parse_and_serialize("fmtp:98 profile-id=2");
+    check_parse_and_serialize("fmtp:98 profile-id=0");
+    check_parse_and_serialize("fmtp:100 max-fs=3600;max-fr=30");
+
+    let fmtp = check_parse("fmtp:98 profile-id=2");
+    assert_eq!(fmtp.parameters.profile_id, Some(2));
+    assert!(fmtp.parameters.unknown_tokens.is_empty());
+
+    let fmtp = check_parse("fmtp:100 max-fr=30;max-fs=3600");
+    assert_eq!(fmtp.parameters.max_fr, 30);
+    assert_eq!(fmtp.parameters.max_fs, 3600);
+    assert_eq!(fmtp.parameters.profile_id, None);
+
+    assert!(parse_attribute("fmtp:98 profile-id=foo").is_err());
+}
+
 #[test]
 fn test_anonymize_attribute_fingerprint() -> Result<(), SdpParserInternalError> {
     let mut anon = StatefulSdpAnonymizer::new();
@@ -404,11 +1559,202 @@ fn test_parse_attribute_group() {
     check_parse_and_serialize("group:DDP L1 L2 L3");
     check_parse_and_serialize("group:BUNDLE sdparta_0 sdparta_1 sdparta_2");
 
-    assert!(parse_attribute("group:").is_err());
-    assert!(matches!(
-        parse_attribute("group:NEVER_SUPPORTED_SEMANTICS"),
-        Err(SdpParserInternalError::Unsupported(_))
-    ));
+    assert!(parse_attribute("group:").is_err());
+    assert!(matches!(
+        parse_attribute("group:NEVER_SUPPORTED_SEMANTICS"),
+        Err(SdpParserInternalError::Unsupported(_))
+    ));
+}
+
+#[test]
+fn test_parse_attribute_group_semantics_case() {
+    let check_parse = make_check_parse!(SdpAttributeGroup, SdpAttribute::Group);
+
+    let bundle = check_parse("group:BUNDLE a b c");
+    assert!(bundle.semantics == SdpAttributeGroupSemantic::Bundle);
+    assert_eq!(bundle.tags, vec!["a", "b", "c"]);
+    assert_eq!(
+        SdpAttribute::Group(bundle).to_string(),
+        "group:BUNDLE a b c"
+    );
+
+    let lowercase = check_parse("group:bundle a");
+    assert!(lowercase.semantics == SdpAttributeGroupSemantic::Bundle);
+    assert_eq!(SdpAttribute::Group(lowercase).to_string(), "group:BUNDLE a");
+
+    let mixed = check_parse("group:Ls 1 2");
+    assert_eq!(SdpAttribute::Group(mixed).to_string(), "group:LS 1 2");
+}
+
+#[test]
+fn test_attribute_type_is_deprecated() {
+    assert!(SdpAttributeType::Sctpmap.is_deprecated());
+    assert!(SdpAttributeType::MsidSemantic.is_deprecated());
+    assert!(SdpAttributeType::SsrcGroup.is_deprecated());
+    assert!(!SdpAttributeType::SctpPort.is_deprecated());
+    assert!(!SdpAttributeType::Msid.is_deprecated());
+    assert!(!SdpAttributeType::Ssrc.is_deprecated());
+}
+
+#[test]
+fn test_attribute_type_from_str() -> Result<(), SdpParserInternalError> {
+    assert!(SdpAttributeType::from_str("remote-candidates")? == SdpAttributeType::RemoteCandidate);
+    assert!(SdpAttributeType::from_str("RTCP-FB")? == SdpAttributeType::Rtcpfb);
+    assert_eq!(
+        SdpAttributeType::from_str("ts-refclk")?.to_string(),
+        "ts-refclk"
+    );
+    match SdpAttributeType::from_str("x-google-flag") {
+        Err(SdpParserInternalError::Unsupported(_)) => (),
+        _ => panic!("x-google-flag is not a known attribute type"),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_attribute_flag() -> Result<(), SdpParserInternalError> {
+    let rtcp_mux = SdpAttribute::flag(SdpAttributeType::RtcpMux)?;
+    assert!(rtcp_mux == SdpAttribute::RtcpMux);
+    assert_eq!(rtcp_mux.to_string(), "rtcp-mux");
+    assert!(SdpAttribute::flag(SdpAttributeType::Inactive)? == SdpAttribute::Inactive);
+
+    match SdpAttribute::flag(SdpAttributeType::Mid) {
+        Err(e) => assert_eq!(
+            e.to_string(),
+            "Parsing error: mid attribute requires a value"
+        ),
+        Ok(_) => unreachable!(),
+    }
+    assert!(SdpAttribute::flag(SdpAttributeType::Candidate).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_attribute_canonicalize() -> Result<(), SdpParserInternalError> {
+    let a = SdpAttribute::from_str("fmtp:109 stereo=1;x-b=2;maxplaybackrate=46000;x-a=1")?;
+    let b = SdpAttribute::from_str("fmtp:109 x-a=1;maxplaybackrate=46000;x-b=2;stereo=1")?;
+    assert!(a != b);
+    assert!(a.canonicalize() == b.canonicalize());
+    assert_eq!(
+        a.canonicalize().to_string(),
+        "fmtp:109 maxplaybackrate=46000;stereo=1;x-a=1;x-b=2"
+    );
+    assert!(SdpAttribute::from_str(&a.canonicalize().to_string())? == a.canonicalize());
+
+    let canonical_pairs = [
+        ("rtpmap:120 VP8/90000", "rtpmap:120 vp8/90000"),
+        ("group:BUNDLE video audio", "group:BUNDLE audio video"),
+        ("ssrc-group:FID 2 1", "ssrc-group:FID 1 2"),
+        (
+            "candidate:0 1 UDP 2122252543 Foo.Local 49760 typ host",
+            "candidate:0 1 UDP 2122252543 foo.local 49760 typ host",
+        ),
+        (
+            "candidate:0 1 dccp 2122252543 172.16.156.106 49760 typ host",
+            "candidate:0 1 DCCP 2122252543 172.16.156.106 49760 typ host",
+        ),
+    ];
+    for (a, b) in &canonical_pairs {
+        let a = SdpAttribute::from_str(a)?.canonicalize();
+        let b = SdpAttribute::from_str(b)?.canonicalize();
+        assert!(a == b);
+        assert_eq!(a.to_string(), b.to_string());
+    }
+
+    let fmtp = SdpAttribute::from_str("fmtp:109 maxplaybackrate=46000;x-b=2;x-a=1")?;
+    assert!(fmtp.canonicalize().canonicalize() == fmtp.canonicalize());
+
+    let dccp =
+        SdpAttribute::from_str("candidate:0 1 dccp 2122252543 172.16.156.106 49760 typ host")?;
+    assert!(
+        dccp != SdpAttribute::from_str(
+            "candidate:0 1 DCCP 2122252543 172.16.156.106 49760 typ host"
+        )?
+    );
+    assert_eq!(
+        dccp.canonicalize().to_string(),
+        "candidate:0 1 DCCP 2122252543 172.16.156.106 49760 typ host"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_group_semantically_eq() -> Result<(), SdpParserInternalError> {
+    let group = |value: &str| match SdpAttribute::from_str(value) {
+        Ok(SdpAttribute::Group(group)) => group,
+        _ => unreachable!(),
+    };
+    let a = group("group:BUNDLE sdparta_0 sdparta_1 sdparta_2");
+    let b = group("group:BUNDLE sdparta_2 sdparta_0 sdparta_1");
+    assert!(a.semantically_eq(&b));
+    assert!(a != b);
+    assert!(a.semantically_eq(&a.clone()));
+    assert!(!a.semantically_eq(&group("group:LS sdparta_0 sdparta_1 sdparta_2")));
+    assert!(!a.semantically_eq(&group("group:BUNDLE sdparta_0 sdparta_1")));
+
+    assert!(SdpAttribute::Group(a.clone()).semantically_eq(&SdpAttribute::Group(b)));
+    assert!(
+        SdpAttribute::from_str("ssrc-group:FID 3156517279 2673335628")?.semantically_eq(
+            &SdpAttribute::from_str("ssrc-group:FID 2673335628 3156517279")?
+        )
+    );
+    assert!(
+        !SdpAttribute::from_str("ssrc-group:FID 3156517279 2673335628")?.semantically_eq(
+            &SdpAttribute::from_str("ssrc-group:FEC 2673335628 3156517279")?
+        )
+    );
+    assert!(SdpAttribute::RtcpMux.semantically_eq(&SdpAttribute::RtcpMux));
+    assert!(!SdpAttribute::RtcpMux.semantically_eq(&SdpAttribute::Group(a)));
+    Ok(())
+}
+
+#[test]
+fn test_bundle_group() -> Result<(), SdpParserInternalError> {
+    let attrs = vec![
+        SdpAttribute::from_str("group:LS 1 2")?,
+        SdpAttribute::from_str("group:BUNDLE sdparta_0 sdparta_1 sdparta_2")?,
+        SdpAttribute::from_str("ice-lite")?,
+    ];
+    assert_eq!(
+        bundle_group(&attrs),
+        Some(vec![
+            "sdparta_0".to_string(),
+            "sdparta_1".to_string(),
+            "sdparta_2".to_string()
+        ])
+    );
+    assert_eq!(bundle_group(&attrs[..1]), None);
+    assert_eq!(bundle_group(&[]), None);
+    Ok(())
+}
+
+#[test]
+fn test_is_ice_lite() -> Result<(), SdpParserInternalError> {
+    assert!(is_ice_lite(&[SdpAttribute::from_str("ice-lite")?]));
+    assert!(is_ice_lite(&[
+        SdpAttribute::from_str("ice-options:trickle")?,
+        SdpAttribute::from_str("ice-lite")?,
+    ]));
+    assert!(!is_ice_lite(&[SdpAttribute::from_str("ice-mismatch")?]));
+    assert!(!is_ice_lite(&[]));
+    Ok(())
+}
+
+#[test]
+fn test_mids() -> Result<(), SdpParserInternalError> {
+    let attrs = vec![SdpAttribute::from_str("mid:sdparta_0")?];
+    assert_eq!(mids(&attrs), vec!["sdparta_0".to_string()]);
+
+    let attrs = vec![
+        SdpAttribute::from_str("mid:b")?,
+        SdpAttribute::from_str("rtcp-mux")?,
+        SdpAttribute::from_str("mid:a")?,
+    ];
+    assert_eq!(mids(&attrs), vec!["b".to_string(), "a".to_string()]);
+    assert_eq!(attrs[0].mid(), Some("b"));
+    assert_eq!(attrs[1].mid(), None);
+    assert!(mids(&[]).is_empty());
+    Ok(())
 }
 
 #[test]
@@ -419,6 +1765,30 @@ fn test_parse_attribute_bundle_only() {
     check_parse_and_serialize("bundle-only");
 
     assert!(parse_attribute("bundle-only foobar").is_err());
+    assert!(parse_attribute("bundle-only:").is_err());
+    assert!(parse_attribute("bundle-only: ").is_err());
+    assert!(parse_attribute("bundle-only:1").is_err());
+}
+
+#[test]
+fn test_parse_value_less_attributes_reject_values() {
+    for name in &[
+        "bundle-only",
+        "end-of-candidates",
+        "extmap-allow-mixed",
+        "ice-lite",
+        "ice-mismatch",
+        "inactive",
+        "recvonly",
+        "rtcp-mux",
+        "rtcp-rsize",
+        "sendonly",
+        "sendrecv",
+    ] {
+        assert!(parse_attribute(name).is_ok());
+        assert!(parse_attribute(&format!("{}:", name)).is_err());
+        assert!(parse_attribute(&format!("{}:1", name)).is_err());
+    }
 }
 
 #[test]
@@ -458,8 +1828,42 @@ fn test_parse_attribute_ice_options() {
         make_check_parse_and_serialize!(check_parse, SdpAttribute::IceOptions);
 
     check_parse_and_serialize("ice-options:trickle");
+    check_parse_and_serialize("ice-options:trickle renomination");
+    assert_eq!(check_parse("ice-options:trickle"), vec!["trickle"]);
 
     assert!(parse_attribute("ice-options:").is_err());
+    match SdpAttribute::from_str("ice-options:  ") {
+        Err(e) => {
+            assert_eq!(e.kind(), SdpErrorKind::MissingToken);
+            assert_eq!(
+                e.to_string(),
+                "Parsing error: ice-options requires at least one option"
+            );
+        }
+        Ok(_) => unreachable!(),
+    }
+}
+
+#[test]
+fn test_parse_attribute_ice_options_typed() -> Result<(), SdpParserInternalError> {
+    let attr = SdpAttribute::from_str("ice-options:trickle renomination ice2 x-foo")?;
+    assert!(
+        attr.ice_options()
+            == Some(vec![
+                SdpIceOption::Trickle,
+                SdpIceOption::Renomination,
+                SdpIceOption::Ice2,
+                SdpIceOption::Other("x-foo".to_string()),
+            ])
+    );
+    assert_eq!(
+        attr.to_string(),
+        "ice-options:trickle renomination ice2 x-foo"
+    );
+    assert!(SdpIceOption::from("Trickle") == SdpIceOption::Trickle);
+    assert_eq!(SdpIceOption::Ice2.to_string(), "ice2");
+    assert!(SdpAttribute::from_str("mid:foo")?.ice_options().is_none());
+    Ok(())
 }
 
 #[test]
@@ -483,8 +1887,44 @@ fn test_parse_attribute_ice_pwd() {
         make_check_parse_and_serialize!(check_parse, SdpAttribute::IcePwd);
 
     check_parse_and_serialize("ice-pwd:e3baa26dd2fa5030d881d385f1e36cce");
+    check_parse_and_serialize("ice-pwd:a+b/cF9e3baa26dd2fa5030d881d385");
+
+    // The default mode only rejects whitespace, so the anonymizer output,
+    // which is not made of ice-chars, and short passwords are accepted
+    check_parse_and_serialize("ice-pwd:ice-password-00000001");
+    check_parse_and_serialize("ice-pwd:e3ba");
 
     assert!(parse_attribute("ice-pwd:").is_err());
+    match parse_attribute("ice-pwd:e3baa26dd2fa5030 d881d385f1e36cce") {
+        Err(e) => {
+            assert_eq!(e.kind(), SdpErrorKind::InvalidValue);
+            assert!(!e.to_string().contains("e3baa26dd2fa5030"));
+        }
+        Ok(_) => unreachable!(),
+    }
+
+    let strict = strict_config();
+    assert!(
+        parse_attribute_with_config("ice-pwd:a+b/cF9e3baa26dd2fa5030d881d385", &strict).is_ok()
+    );
+    assert!(
+        parse_attribute_with_config("ice-pwd:e3baa26dd2fa5030 d881d385f1e36cce", &strict).is_err()
+    );
+    for pwd in &[
+        "e3baa26dd2fa5030-d881d385f1e36cce".to_string(),
+        "f".repeat(21),
+        "f".repeat(257),
+    ] {
+        match parse_attribute_with_config(&format!("ice-pwd:{}", pwd), &strict) {
+            Err(e) => {
+                assert_eq!(e.kind(), SdpErrorKind::InvalidValue);
+                assert!(!e.to_string().contains(pwd.as_str()));
+            }
+            Ok(_) => unreachable!(),
+        }
+    }
+    assert!(parse_attribute_with_config(&format!("ice-pwd:{}", "f".repeat(22)), &strict).is_ok());
+    assert!(parse_attribute_with_config(&format!("ice-pwd:{}", "f".repeat(256)), &strict).is_ok());
 }
 
 #[test]
@@ -494,8 +1934,32 @@ fn test_parse_attribute_ice_ufrag() {
         make_check_parse_and_serialize!(check_parse, SdpAttribute::IceUfrag);
 
     check_parse_and_serialize("ice-ufrag:58b99ead");
+    check_parse_and_serialize("ice-ufrag:5+b/");
+
+    // Like ice-pwd the default mode accepts characters outside of ice-char
+    check_parse_and_serialize("ice-ufrag:ice-user-00000001");
+    check_parse_and_serialize("ice-ufrag:ab-c");
+    check_parse_and_serialize("ice-ufrag:58b_.");
+    check_parse_and_serialize("ice-ufrag:58b");
 
     assert!(parse_attribute("ice-ufrag:").is_err());
+    match parse_attribute("ice-ufrag:58b9 9ead") {
+        Err(e) => {
+            assert_eq!(e.kind(), SdpErrorKind::InvalidValue);
+            assert!(!e.to_string().contains("58b9"));
+        }
+        Ok(_) => unreachable!(),
+    }
+
+    let strict = strict_config();
+    assert!(parse_attribute_with_config("ice-ufrag:5+b/", &strict).is_ok());
+    assert!(parse_attribute_with_config("ice-ufrag:58b9 9ead", &strict).is_err());
+    for ufrag in &["58b9_ead", "ab-c", "5+b"] {
+        match parse_attribute_with_config(&format!("ice-ufrag:{}", ufrag), &strict) {
+            Err(e) => assert_eq!(e.kind(), SdpErrorKind::InvalidValue),
+            Ok(_) => unreachable!(),
+        }
+    }
 }
 
 #[test]
@@ -671,6 +2135,43 @@ fn test_parse_attribute_label() {
     assert!(parse_attribute("label:").is_err());
 }
 
+#[test]
+fn test_parse_attribute_maxprate() {
+    let check_parse = make_check_parse!(f64, SdpAttribute::MaxPrate);
+    let check_parse_and_serialize =
+        make_check_parse_and_serialize!(check_parse, SdpAttribute::MaxPrate);
+
+    check_parse_and_serialize("maxprate:30.5");
+    check_parse_and_serialize("maxprate:60");
+    assert!((check_parse("maxprate:30.5") - 30.5).abs() < f64::EPSILON);
+
+    assert!(parse_attribute("maxprate:").is_err());
+    assert!(parse_attribute("maxprate:30 1").is_err());
+    assert!(parse_attribute("maxprate:foobar").is_err());
+    assert!(parse_attribute("maxprate:0").is_err());
+    assert!(parse_attribute("maxprate:-1").is_err());
+    assert!(parse_attribute("maxprate:inf").is_err());
+}
+
+#[test]
+fn test_parse_attribute_framerate() {
+    let check_parse = make_check_parse!(f64, SdpAttribute::Framerate);
+    let check_parse_and_serialize =
+        make_check_parse_and_serialize!(check_parse, SdpAttribute::Framerate);
+
+    check_parse_and_serialize("framerate:29.97");
+    check_parse_and_serialize("framerate:30");
+
+    assert!(parse_attribute("framerate:").is_err());
+    assert!(parse_attribute("framerate:NaN").is_err());
+    assert!(!SdpAttribute::from_str("framerate:30")
+        .unwrap()
+        .allowed_at_session_level());
+    assert!(SdpAttribute::from_str("maxprate:30")
+        .unwrap()
+        .allowed_at_session_level());
+}
+
 #[test]
 fn test_parse_attribute_maxptime() {
     let check_parse = make_check_parse!(u64, SdpAttribute::MaxPtime);
@@ -684,6 +2185,33 @@ fn test_parse_attribute_maxptime() {
     assert!(parse_attribute("maxptime:foobar").is_err());
 }
 
+#[test]
+fn test_parse_attribute_mediaclk() {
+    let check_parse = make_check_parse!(SdpAttributeMediaClk, SdpAttribute::MediaClk);
+    let check_parse_and_serialize =
+        make_check_parse_and_serialize!(check_parse, SdpAttribute::MediaClk);
+
+    check_parse_and_serialize("mediaclk:direct=0");
+    check_parse_and_serialize("mediaclk:direct");
+    check_parse_and_serialize("mediaclk:sender");
+    check_parse_and_serialize("mediaclk:direct=963214424 rate=1000/1001");
+
+    assert!(
+        check_parse("mediaclk:direct=0")
+            == SdpAttributeMediaClk::Direct {
+                offset: Some(0),
+                rate: None,
+            }
+    );
+
+    assert!(parse_attribute("mediaclk:").is_err());
+    assert!(parse_attribute("mediaclk:direct=foo").is_err());
+    assert!(parse_attribute("mediaclk:direct=0 rate=1000/0").is_err());
+    assert!(parse_attribute("mediaclk:sender rate=1000/1001").is_err());
+    assert!(parse_attribute("mediaclk:direct=0 foo").is_err());
+    assert!(parse_attribute("mediaclk:IEEE1722=38-D6-6D-8E-D2-78-13-2F").is_err());
+}
+
 #[test]
 fn test_parse_attribute_mid() {
     let check_parse = make_check_parse!(String, SdpAttribute::Mid);
@@ -705,6 +2233,14 @@ fn test_parse_attribute_msid() {
     check_parse_and_serialize(
         "msid:{5a990edd-0568-ac40-8d97-310fc33f3411} {218cfa1c-617d-2249-9997-60929ce4c405}",
     );
+    check_parse_and_serialize("msid:- {218cfa1c-617d-2249-9997-60929ce4c405}");
+
+    assert!(check_parse("msid:stream track").id == MsidId::Id("stream".to_string()));
+    let no_stream = check_parse("msid:- track");
+    assert!(no_stream.id == MsidId::NoStream);
+    assert_eq!(no_stream.appdata, Some("track".to_string()));
+    assert!(check_parse("msid:-").id == MsidId::NoStream);
+    assert!(check_parse("msid:-foo").id == MsidId::Id("-foo".to_string()));
 
     assert!(parse_attribute("msid:").is_err());
 }
@@ -867,6 +2403,26 @@ fn test_parse_attribute_setup() {
     assert!(parse_attribute("setup:foobar").is_err());
 }
 
+#[test]
+fn test_parse_attribute_setup_case_and_whitespace() {
+    let check_parse = make_check_parse!(SdpAttributeSetup, SdpAttribute::Setup);
+
+    for (line, expected) in &[
+        ("setup:ACTPASS", SdpAttributeSetup::Actpass),
+        ("setup: active ", SdpAttributeSetup::Active),
+        ("  setup:Passive", SdpAttributeSetup::Passive),
+        ("setup:\tHoldConn\t", SdpAttributeSetup::Holdconn),
+    ] {
+        let setup = check_parse(line);
+        assert!(setup == *expected);
+        assert_eq!(
+            SdpAttribute::Setup(setup).to_string(),
+            format!("setup:{}", expected)
+        );
+    }
+    assert!(parse_attribute("setup:act pass").is_err());
+}
+
 #[test]
 fn test_parse_attribute_rtcp() {
     let check_parse = make_check_parse!(SdpAttributeRtcp, SdpAttribute::Rtcp);
@@ -880,10 +2436,46 @@ fn test_parse_attribute_rtcp() {
     assert!(parse_attribute("rtcp:").is_err());
     assert!(parse_attribute("rtcp:70000").is_err());
     assert!(parse_attribute("rtcp:9 IN").is_err());
-    assert!(parse_attribute("rtcp:9 IN IP4").is_err());
     assert!(parse_attribute("rtcp:9 IN IP4 ::1").is_err());
 }
 
+#[test]
+fn test_parse_attribute_rtcp_without_address() {
+    let check_parse = make_check_parse!(SdpAttributeRtcp, SdpAttribute::Rtcp);
+    let strict = strict_config();
+
+    let rtcp = check_parse("rtcp:9 IN IP4");
+    assert_eq!(rtcp.port, 9);
+    assert!(rtcp.unicast_addr.is_none());
+    assert!(check_parse("rtcp:9 IN IP6").unicast_addr.is_none());
+    assert!(parse_attribute("rtcp:9 IN IP5").is_err());
+
+    match parse_attribute_with_config("rtcp:9 IN IP4", &strict) {
+        Err(e) => assert_eq!(e.kind(), SdpErrorKind::MissingToken),
+        Ok(_) => unreachable!(),
+    }
+    assert!(parse_attribute_with_config("rtcp:5000", &strict).is_ok());
+    assert!(parse_attribute_with_config("rtcp:9 IN IP4 0.0.0.0", &strict).is_ok());
+    assert!(check_parse("rtcp:9 IN IP4 0.0.0.0").unicast_addr.is_some());
+}
+
+#[test]
+fn test_parse_attribute_rtcp_address_family_mismatch() {
+    for line in &["rtcp:9 IN IP6 0.0.0.0", "rtcp:9 IN IP4 2001:db8::1"] {
+        match SdpAttribute::from_str(line) {
+            Err(e) => {
+                assert_eq!(e.kind(), SdpErrorKind::AddressFamilyMismatch);
+                assert!(e.to_string().contains("different type"));
+            }
+            Ok(_) => unreachable!(),
+        }
+    }
+    match SdpAttribute::from_str("rtcp:9 IN IP6 2001:db8::zz") {
+        Err(e) => assert_ne!(e.kind(), SdpErrorKind::AddressFamilyMismatch),
+        Ok(_) => unreachable!(),
+    }
+}
+
 #[test]
 fn test_parse_attribute_rtcp_fb() {
     let check_parse = make_check_parse!(SdpAttributeRtcpFb, SdpAttribute::Rtcpfb);
@@ -905,6 +2497,8 @@ fn test_parse_attribute_rtcp_fb() {
     check_parse_and_serialize("rtcp-fb:101 trr-int 1");
     check_parse_and_serialize("rtcp-fb:101 goog-remb");
     check_parse_and_serialize("rtcp-fb:101 transport-cc");
+    check_parse_and_serialize("rtcp-fb:101 app");
+    check_parse_and_serialize("rtcp-fb:101 app foo");
 
     assert!(parse_attribute("rtcp-fb:101 unknown").is_err());
     assert!(parse_attribute("rtcp-fb:101 ack").is_err());
@@ -916,6 +2510,31 @@ fn test_parse_attribute_rtcp_fb() {
     assert!(parse_attribute("rtcp-fb:101 transport-cc unknown").is_err());
 }
 
+#[test]
+fn test_parse_attribute_rtcp_fb_feedback_types() {
+    let check_parse = make_check_parse!(SdpAttributeRtcpFb, SdpAttribute::Rtcpfb);
+
+    for (line, feedback_type, parameter) in &[
+        (
+            "rtcp-fb:96 transport-cc",
+            SdpAttributeRtcpFbType::TransCc,
+            "",
+        ),
+        ("rtcp-fb:96 goog-remb", SdpAttributeRtcpFbType::Remb, ""),
+        ("rtcp-fb:96 nack", SdpAttributeRtcpFbType::Nack, ""),
+        ("rtcp-fb:96 nack pli", SdpAttributeRtcpFbType::Nack, "pli"),
+        ("rtcp-fb:96 ccm fir", SdpAttributeRtcpFbType::Ccm, "fir"),
+        ("rtcp-fb:96 app foo", SdpAttributeRtcpFbType::App, "foo"),
+        ("rtcp-fb:96 app", SdpAttributeRtcpFbType::App, ""),
+    ] {
+        let rtcpfb = check_parse(line);
+        assert!(rtcpfb.payload_type == SdpAttributePayloadType::PayloadType(96));
+        assert!(rtcpfb.feedback_type == *feedback_type);
+        assert_eq!(rtcpfb.parameter, *parameter);
+        assert!(rtcpfb.extra.is_empty());
+    }
+}
+
 #[test]
 fn test_parse_attribute_rtcp_mux() {
     let check_parse = make_check_parse!(SdpAttribute::RtcpMux);
@@ -925,6 +2544,23 @@ fn test_parse_attribute_rtcp_mux() {
     assert!(parse_attribute("rtcp-mux foobar").is_err());
 }
 
+#[test]
+fn test_parse_attribute_name_case_insensitive() -> Result<(), SdpParserInternalError> {
+    for (upper, lower) in &[
+        ("RTCP-MUX", "rtcp-mux"),
+        ("MID:sdparta_0", "mid:sdparta_0"),
+        ("RtpMap:109 opus/48000/2", "rtpmap:109 opus/48000/2"),
+        (
+            "CANDIDATE:0 1 UDP 2122252543 172.16.156.106 49760 typ host",
+            "candidate:0 1 UDP 2122252543 172.16.156.106 49760 typ host",
+        ),
+    ] {
+        assert!(SdpAttribute::from_str(upper)? == SdpAttribute::from_str(lower)?);
+    }
+    assert!(SdpAttribute::from_str("mid:FOO")? != SdpAttribute::from_str("mid:foo")?);
+    Ok(())
+}
+
 #[test]
 fn test_parse_attribute_rtcp_rsize() {
     let check_parse = make_check_parse!(SdpAttribute::RtcpRsize);
@@ -945,10 +2581,96 @@ fn test_parse_attribute_rtpmap() {
 
     assert!(parse_attribute("rtpmap: ").is_err());
     assert!(parse_attribute("rtpmap:109 ").is_err());
-    assert!(parse_attribute("rtpmap:109 opus").is_err());
+    let strict = strict_config();
+    assert!(parse_attribute_with_config("rtpmap:109 opus", &strict).is_err());
     assert!(parse_attribute("rtpmap:128 opus/48000").is_err());
 }
 
+#[test]
+fn test_parse_attribute_rtpmap_trailing_slash() {
+    for (line, message) in &[
+        ("rtpmap:96 VP8/90000/", "empty channels token"),
+        ("rtpmap:96 VP8/", "empty clock rate token"),
+    ] {
+        match parse_attribute(line) {
+            Err(e) => {
+                assert_eq!(e.kind(), SdpErrorKind::MissingToken);
+                assert!(e.to_string().contains(message));
+            }
+            Ok(_) => unreachable!(),
+        }
+    }
+    assert!(parse_attribute("rtpmap:96 VP8/90000").is_ok());
+}
+
+#[test]
+fn test_rtpmap_codec_matches() {
+    let check_parse = make_check_parse!(SdpAttributeRtpmap, SdpAttribute::Rtpmap);
+
+    let opus = check_parse("rtpmap:109 opus/48000/2");
+    assert_eq!(opus.codec_name, "opus");
+    assert!(opus.codec_matches("opus"));
+    assert!(opus.codec_matches("OPUS"));
+    assert!(!opus.codec_matches("opu"));
+
+    let vp8 = check_parse("rtpmap:120 VP8/90000");
+    assert_eq!(vp8.codec_name, "VP8");
+    assert!(vp8.codec_matches("vp8"));
+    assert!(!vp8.codec_matches("VP9"));
+    assert!(check_parse("rtpmap:126 h264/90000").codec_matches("H264"));
+}
+
+#[test]
+fn test_parse_attribute_rtpmap_without_clock_rate() {
+    let check_parse = make_check_parse!(SdpAttributeRtpmap, SdpAttribute::Rtpmap);
+    let check_parse_and_serialize =
+        make_check_parse_and_serialize!(check_parse, SdpAttribute::Rtpmap);
+
+    check_parse_and_serialize("rtpmap:96 VP8");
+    check_parse_and_serialize("rtpmap:96 VP8/0");
+    check_parse_and_serialize("rtpmap:96 opus/0/2");
+    let rtpmap = check_parse("rtpmap:96 VP8");
+    assert_eq!(rtpmap.codec_name, "VP8");
+    assert_eq!(rtpmap.frequency, None);
+    assert_eq!(rtpmap.effective_frequency(), None);
+    assert_eq!(check_parse("rtpmap:96 VP8/0").frequency, Some(0));
+    assert_eq!(
+        check_parse("rtpmap:0 PCMU").effective_frequency(),
+        Some(8000)
+    );
+
+    let strict = strict_config();
+    assert!(parse_attribute_with_config("rtpmap:96 VP8", &strict).is_err());
+    assert!(parse_attribute_with_config("rtpmap:96 VP8/90000", &strict).is_ok());
+}
+
+#[test]
+fn test_rtpmap_effective_frequency() {
+    let check_parse = make_check_parse!(SdpAttributeRtpmap, SdpAttribute::Rtpmap);
+
+    assert_eq!(
+        check_parse("rtpmap:109 opus/48000/2").effective_frequency(),
+        Some(48000)
+    );
+    assert_eq!(
+        check_parse("rtpmap:0 PCMU/8000").effective_frequency(),
+        Some(8000)
+    );
+    assert_eq!(
+        check_parse("rtpmap:0 PCMU").effective_frequency(),
+        Some(8000)
+    );
+    assert_eq!(
+        check_parse("rtpmap:34 H263").effective_frequency(),
+        Some(90000)
+    );
+    assert_eq!(check_parse("rtpmap:109 opus").effective_frequency(), None);
+    assert_eq!(
+        SdpAttributeRtpmap::new(0, "PCMU".to_string(), 16000).effective_frequency(),
+        Some(16000)
+    );
+}
+
 #[test]
 fn test_parse_attribute_sctpmap() {
     let check_parse = make_check_parse!(SdpAttributeSctpmap, SdpAttribute::Sctpmap);
@@ -1015,6 +2737,31 @@ fn test_parse_attribute_simulcast() {
     assert!(parse_attribute("simulcast: send foo=8;10").is_err());
 }
 
+#[test]
+fn test_parse_attribute_simulcast_separator_whitespace() {
+    let check_parse = make_check_parse!(SdpAttributeSimulcast, SdpAttribute::Simulcast);
+
+    for (spaced, compact) in &[
+        ("simulcast:send 1 ; 2", "simulcast:send 1;2"),
+        ("simulcast:send 1 ,2; ~3", "simulcast:send 1,2;~3"),
+        (
+            "simulcast:send 1; 4 , 5 recv 6 ;7",
+            "simulcast:send 1;4,5 recv 6;7",
+        ),
+        ("simulcast: send rid = foo ; bar", "simulcast:send foo;bar"),
+    ] {
+        assert_eq!(
+            SdpAttribute::Simulcast(check_parse(spaced)).to_string(),
+            *compact
+        );
+    }
+
+    assert!(parse_attribute("simulcast:send 1;;2").is_err());
+    assert!(parse_attribute("simulcast:send 1 , ,2").is_err());
+    assert!(parse_attribute("simulcast:send 1;").is_err());
+    assert!(parse_attribute("simulcast:send ~").is_err());
+}
+
 #[test]
 fn test_parse_attribute_ssrc() {
     let check_parse = make_check_parse!(SdpAttributeSsrc, SdpAttribute::Ssrc);
@@ -1030,6 +2777,33 @@ fn test_parse_attribute_ssrc() {
     assert!(parse_attribute("ssrc:foo").is_err());
 }
 
+#[test]
+fn test_parse_attribute_ssrc_sub_attributes() {
+    let check_parse = make_check_parse!(SdpAttributeSsrc, SdpAttribute::Ssrc);
+    let check_parse_and_serialize =
+        make_check_parse_and_serialize!(check_parse, SdpAttribute::Ssrc);
+
+    check_parse_and_serialize("ssrc:123 previous-ssrc:456");
+    check_parse_and_serialize("ssrc:123 fmtp:96 apt=100");
+
+    let ssrc = check_parse("ssrc:123 previous-ssrc:456");
+    assert_eq!(ssrc.attribute, Some("previous-ssrc".to_string()));
+    assert_eq!(ssrc.value, Some("456".to_string()));
+    assert_eq!(ssrc.previous_ssrc(), Some(456));
+    assert_eq!(ssrc.fmtp(), None);
+
+    let ssrc = check_parse("ssrc:123 fmtp:96 apt=100");
+    assert_eq!(ssrc.value, Some("96 apt=100".to_string()));
+    assert_eq!(ssrc.fmtp(), Some("96 apt=100"));
+    assert_eq!(ssrc.previous_ssrc(), None);
+
+    assert_eq!(
+        check_parse("ssrc:123 previous-ssrc:foo").previous_ssrc(),
+        None
+    );
+    assert_eq!(check_parse("ssrc:123 previous-ssrc").previous_ssrc(), None);
+}
+
 #[test]
 fn test_anonymize_attribute_ssrc() -> Result<(), SdpParserInternalError> {
     let mut anon = StatefulSdpAnonymizer::new();
@@ -1087,7 +2861,370 @@ fn test_parse_attribute_ssrc_group() {
     assert!(parse_attribute("ssrc-group:FID").is_err());
 }
 
+#[test]
+fn test_parse_attribute_ssrc_group_sim() {
+    let parsed = parse_attribute("ssrc-group:SIM 1 2 3");
+    match parsed {
+        Ok(SdpType::Attribute(SdpAttribute::SsrcGroup(semantic, ssrcs))) => {
+            assert!(semantic == SdpSsrcGroupSemantic::Sim);
+            let ids: Vec<u32> = ssrcs.iter().map(|ssrc| ssrc.id).collect();
+            assert_eq!(ids, [1, 2, 3]);
+        }
+        Err(e) => panic!("{}", e),
+        _ => unreachable!(),
+    }
+
+    let parsed = parse_attribute("ssrc-group:SIM 1 2 3");
+    match parsed {
+        Ok(SdpType::Attribute(attr)) => assert_eq!(attr.to_string(), "ssrc-group:SIM 1 2 3"),
+        Err(e) => panic!("{}", e),
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn test_parse_attribute_ts_refclk() {
+    let check_parse = make_check_parse!(SdpAttributeTsRefclk, SdpAttribute::TsRefclk);
+    let check_parse_and_serialize =
+        make_check_parse_and_serialize!(check_parse, SdpAttribute::TsRefclk);
+
+    check_parse_and_serialize("ts-refclk:ptp=IEEE1588-2008:39-A7-94-FF-FE-07-CB-D0:37");
+    check_parse_and_serialize("ts-refclk:ptp=IEEE1588-2008:39-A7-94-FF-FE-07-CB-D0");
+    check_parse_and_serialize("ts-refclk:ptp=IEEE1588-2008:traceable");
+    check_parse_and_serialize("ts-refclk:ntp=203.0.113.10");
+    check_parse_and_serialize("ts-refclk:ntp=/traceable/");
+    check_parse_and_serialize("ts-refclk:local");
+
+    assert!(
+        check_parse("ts-refclk:ptp=IEEE1588-2008:39-A7-94-FF-FE-07-CB-D0:37")
+            == SdpAttributeTsRefclk::Ptp {
+                version: "IEEE1588-2008".to_string(),
+                grandmaster: Some("39-A7-94-FF-FE-07-CB-D0".to_string()),
+                domain: Some(37),
+            }
+    );
+    assert!(
+        check_parse("ts-refclk:ptp=IEEE1588-2008:traceable")
+            == SdpAttributeTsRefclk::Ptp {
+                version: "IEEE1588-2008".to_string(),
+                grandmaster: None,
+                domain: None,
+            }
+    );
+
+    assert!(parse_attribute("ts-refclk:").is_err());
+    assert!(parse_attribute("ts-refclk:ntp=").is_err());
+    assert!(parse_attribute("ts-refclk:ptp=IEEE1588-2008").is_err());
+    assert!(parse_attribute("ts-refclk:ptp=IEEE1588-2008:traceable:37").is_err());
+    assert!(parse_attribute("ts-refclk:ptp=IEEE1588-2008:39-A7-94-FF-FE-07-CB-D0:128").is_err());
+    assert!(parse_attribute("ts-refclk:local=foo").is_err());
+    assert!(parse_attribute("ts-refclk:gps").is_err());
+}
+
 #[test]
 fn test_parse_unknown_attribute() {
     assert!(parse_attribute("unknown").is_err())
 }
+
+#[test]
+fn test_attribute_sctp_port() -> Result<(), SdpParserInternalError> {
+    assert_eq!(
+        SdpAttribute::from_str("sctp-port:5000")?.sctp_port(),
+        Some(5000)
+    );
+    assert_eq!(
+        SdpAttribute::from_str("sctpmap:5000 webrtc-datachannel 256")?.sctp_port(),
+        Some(5000)
+    );
+    assert_eq!(SdpAttribute::from_str("mid:5000")?.sctp_port(), None);
+    Ok(())
+}
+
+#[test]
+fn test_attribute_is_kind() -> Result<(), SdpParserInternalError> {
+    let candidate =
+        SdpAttribute::from_str("candidate:0 1 UDP 2122252543 172.16.156.106 49760 typ host")?;
+    assert!(candidate.is_kind(&SdpAttributeType::Candidate));
+    assert!(!candidate.is_kind(&SdpAttributeType::Mid));
+    assert!(SdpAttribute::from_str("mid:sdparta_0")?.is_kind(&SdpAttributeType::Mid));
+    Ok(())
+}
+
+#[test]
+fn test_attribute_type_ordering() -> Result<(), SdpParserInternalError> {
+    let mut attrs = [
+        SdpAttribute::from_str("rtcp-mux")?,
+        SdpAttribute::from_str("mid:sdparta_0")?,
+        SdpAttribute::from_str("candidate:0 1 UDP 2122252543 172.16.156.106 49760 typ host")?,
+        SdpAttribute::from_str("bundle-only")?,
+    ];
+    attrs.sort_by_key(|a| SdpAttributeType::from(a));
+    let names: Vec<String> = attrs
+        .iter()
+        .map(|a| SdpAttributeType::from(a).to_string())
+        .collect();
+    assert_eq!(names, ["bundle-only", "candidate", "mid", "rtcp-mux"]);
+
+    assert!(SdpAttributeType::BundleOnly < SdpAttributeType::Candidate);
+    assert!(SdpAttributeType::Sendrecv > SdpAttributeType::Candidate);
+    Ok(())
+}
+
+#[test]
+fn test_media_attributes_to_sdp() -> Result<(), SdpParserInternalError> {
+    let attrs = [
+        "end-of-candidates",
+        "candidate:0 1 UDP 2122252543 172.16.156.106 49760 typ host",
+        "fmtp:120 max-fs=12288;max-fr=60",
+        "rtcp-fb:* nack",
+        "fmtp:109 maxplaybackrate=46000;stereo=1;useinbandfec=1",
+        "rtpmap:109 opus/48000/2",
+        "rtcp-mux",
+        "rtpmap:120 VP8/90000",
+        "rtcp-fb:120 nack pli",
+        "mid:sdparta_0",
+        "sendrecv",
+    ]
+    .iter()
+    .map(|line| SdpAttribute::from_str(line))
+    .collect::<Result<Vec<_>, _>>()?;
+
+    assert_eq!(
+        media_attributes_to_sdp(&attrs),
+        "a=sendrecv\r\n\
+         a=mid:sdparta_0\r\n\
+         a=rtcp-mux\r\n\
+         a=rtpmap:109 opus/48000/2\r\n\
+         a=fmtp:109 maxplaybackrate=46000;stereo=1;useinbandfec=1\r\n\
+         a=rtpmap:120 VP8/90000\r\n\
+         a=rtcp-fb:120 nack pli\r\n\
+         a=fmtp:120 max-fs=12288;max-fr=60\r\n\
+         a=rtcp-fb:* nack\r\n\
+         a=candidate:0 1 UDP 2122252543 172.16.156.106 49760 typ host\r\n\
+         a=end-of-candidates\r\n"
+    );
+    assert_eq!(media_attributes_to_sdp(&[]), "");
+    Ok(())
+}
+
+#[test]
+fn test_media_attributes_to_sdp_duplicate_rtpmap() -> Result<(), SdpParserInternalError> {
+    let attrs = [
+        "rtpmap:120 VP8/90000",
+        "rtcp-fb:120 nack pli",
+        "fmtp:120 max-fs=12288;max-fr=60",
+        "rtpmap:120 VP8/90000",
+    ]
+    .iter()
+    .map(|line| SdpAttribute::from_str(line))
+    .collect::<Result<Vec<_>, _>>()?;
+
+    assert_eq!(
+        media_attributes_to_sdp(&attrs),
+        "a=rtpmap:120 VP8/90000\r\n\
+         a=rtcp-fb:120 nack pli\r\n\
+         a=fmtp:120 max-fs=12288;max-fr=60\r\n\
+         a=rtpmap:120 VP8/90000\r\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn test_media_attributes_to_sdp_with_pt_order() -> Result<(), SdpParserInternalError> {
+    let attrs = [
+        "rtpmap:109 opus/48000/2",
+        "fmtp:109 maxplaybackrate=46000",
+        "rtpmap:9 G722/8000/1",
+        "rtpmap:0 PCMU/8000",
+        "rtpmap:120 VP8/90000",
+        "rtcp-fb:120 nack pli",
+    ]
+    .iter()
+    .map(|line| SdpAttribute::from_str(line))
+    .collect::<Result<Vec<_>, _>>()?;
+
+    assert_eq!(
+        media_attributes_to_sdp_with_pt_order(&attrs, &[0, 120, 109]),
+        "a=rtpmap:0 PCMU/8000\r\n\
+         a=rtpmap:120 VP8/90000\r\n\
+         a=rtcp-fb:120 nack pli\r\n\
+         a=rtpmap:109 opus/48000/2\r\n\
+         a=fmtp:109 maxplaybackrate=46000\r\n\
+         a=rtpmap:9 G722/8000/1\r\n"
+    );
+    assert_eq!(
+        media_attributes_to_sdp_with_pt_order(&attrs, &[]),
+        media_attributes_to_sdp(&attrs)
+    );
+    assert_eq!(
+        media_attributes_to_sdp_with_pt_order(&attrs, &[9, 126]),
+        media_attributes_to_sdp_with_pt_order(&attrs, &[9, 109, 0, 120])
+    );
+    Ok(())
+}
+
+#[test]
+fn test_tokens_split_whitespace() {
+    let mut offsets = Vec::new();
+    for line in ["", "  ", "a", " a  b\tc ", "typ\u{a0}host x"].iter() {
+        let tokens = Tokens::split_whitespace(line, &mut offsets);
+        let expected: Vec<&str> = line.split_whitespace().collect();
+        assert_eq!(tokens.len(), expected.len());
+        for (index, token) in expected.iter().enumerate() {
+            assert_eq!(&tokens[index], *token);
+        }
+    }
+}
+
+#[test]
+fn test_attribute_parser_reuse() -> Result<(), SdpParserInternalError> {
+    let mut parser = SdpAttributeParser::default();
+    let lines = [
+        "candidate:0 1 UDP 2122252543 172.16.156.106 49760 typ host generation 0",
+        "RTPMAP:109 opus/48000/2",
+        "candidate:1 1 UDP 1685987071 24.23.204.141 54609 typ srflx raddr 192.168.1.4 rport 61665",
+        "candidate:2 1 TCP 2105524479 10.0.0.1 9 typ host tcptype active",
+        "mid:sdparta_0",
+    ];
+    for line in lines.iter() {
+        assert_eq!(parser.parse(line)?, SdpAttribute::from_str(line)?);
+    }
+
+    let mut attribute = SdpAttribute::RtcpMux;
+    parser.parse_into("ice-ufrag:4a799b2e", &mut attribute)?;
+    assert_eq!(attribute, SdpAttribute::IceUfrag("4a799b2e".to_string()));
+    assert!(parser
+        .parse_into(
+            "candidate:0 1 UDP 2122252543 172.16.156.106",
+            &mut attribute
+        )
+        .is_err());
+    assert_eq!(attribute, SdpAttribute::IceUfrag("4a799b2e".to_string()));
+    assert!(parser.parse("rtcp-mux:foo").is_err());
+    assert_eq!(parser.parse(" sendrecv ")?, SdpAttribute::Sendrecv);
+    Ok(())
+}
+
+#[test]
+fn test_attribute_line_reader() -> Result<(), SdpParserInternalError> {
+    let mut reader = SdpAttributeLineReader::default();
+    assert!(reader.push(b"a=candidate:0 1 UDP 2122").is_empty());
+    assert!(reader.push(b"252543 172.16.156.106 49760 ").is_empty());
+    assert!(reader.has_partial_line());
+    let mut parsed = reader.push(b"typ host\r\n");
+    assert_eq!(parsed.len(), 1);
+    assert!(!reader.has_partial_line());
+    match parsed.remove(0)? {
+        SdpAttribute::Candidate(candidate) => {
+            assert_eq!(candidate.port, 49760);
+            assert_eq!(candidate.c_type, SdpAttributeCandidateType::Host);
+        }
+        _ => unreachable!(),
+    }
+
+    let parsed = reader.push(b"a=rtcp-mux\r\n\r\na=ptime:foo\r\na=mid:");
+    assert_eq!(parsed.len(), 2);
+    assert!(parsed[0].is_ok());
+    assert!(parsed[1].is_err());
+    assert!(reader.has_partial_line());
+    let parsed = reader.push(b"sdparta_0\r\n");
+    assert_eq!(parsed.len(), 1);
+    assert_eq!(parsed[0].as_ref().unwrap().to_string(), "mid:sdparta_0");
+
+    assert!(reader.push(b"a=mid:\xff\r\n")[0].is_err());
+
+    let parsed = reader.push(b"a=rtcp-mux\na=mid:");
+    assert_eq!(parsed.len(), 1);
+    assert!(parsed[0].is_ok());
+    let parsed = reader.push(b"foo\n");
+    assert_eq!(parsed[0].as_ref().unwrap().to_string(), "mid:foo");
+    assert!(!reader.has_partial_line());
+    Ok(())
+}
+
+#[test]
+fn test_attribute_line_reader_max_line_length() {
+    let mut reader = SdpAttributeLineReader::default().with_max_line_length(12);
+    assert!(reader.push(b"a=mid:0123").is_empty());
+    let parsed = reader.push(b"456789");
+    assert_eq!(parsed.len(), 1);
+    assert!(parsed[0].is_err());
+    assert!(!reader.has_partial_line());
+    // The rest of the over-long line is dropped without further errors
+    assert!(reader.push(b"0123456789abcdef").is_empty());
+    let parsed = reader.push(b"\r\na=mid:foo\r\n");
+    assert_eq!(parsed.len(), 1);
+    assert_eq!(parsed[0].as_ref().unwrap().to_string(), "mid:foo");
+
+    let parsed = reader.push(b"a=mid:0123456789\r\na=rtcp-mux\r\n");
+    assert_eq!(parsed.len(), 2);
+    assert!(parsed[0].is_err());
+    assert!(parsed[1].is_ok());
+}
+
+#[test]
+fn test_parse_attribute_strict_trailing_tokens() {
+    let strict = strict_config();
+
+    assert!(parse_attribute("mid:foo bar").is_ok());
+    let error = parse_attribute_with_config("mid:foo bar", &strict)
+        .err()
+        .unwrap();
+    assert_eq!(error.kind(), SdpErrorKind::UnexpectedToken);
+    assert!(parse_attribute_with_config("mid:foo", &strict).is_ok());
+
+    assert!(parse_attribute("msid:stream track extra").is_ok());
+    assert!(parse_attribute_with_config("msid:stream track extra", &strict).is_err());
+    assert!(parse_attribute_with_config("msid:stream track", &strict).is_ok());
+
+    assert!(parse_attribute_with_config("rtcp:9 IN IP4 0.0.0.0", &strict).is_ok());
+    assert!(parse_attribute_with_config("rtcp:9 IN IP4 0.0.0.0 foo", &strict).is_err());
+
+    // Variadic attributes are not affected
+    assert!(parse_attribute_with_config(
+        "candidate:0 1 UDP 2122252543 172.16.156.106 49760 typ host generation 0",
+        &strict
+    )
+    .is_ok());
+    assert!(parse_attribute_with_config("group:BUNDLE 0 1 2", &strict).is_ok());
+}
+
+#[test]
+fn test_referenced_payload_types() -> Result<(), SdpParserInternalError> {
+    let attrs = vec![
+        SdpAttribute::from_str("rtpmap:109 opus/48000/2")?,
+        SdpAttribute::from_str("rtpmap:9 G722/8000/1")?,
+        SdpAttribute::from_str("fmtp:109 maxplaybackrate=48000;stereo=1;useinbandfec=1")?,
+        SdpAttribute::from_str("fmtp:101 0-15")?,
+        SdpAttribute::from_str("rtcp-fb:126 nack")?,
+        SdpAttribute::from_str("rtcp-fb:* ccm fir")?,
+        SdpAttribute::from_str("mid:sdparta_0")?,
+    ];
+    assert_eq!(
+        referenced_payload_types(&attrs)
+            .into_iter()
+            .collect::<Vec<u8>>(),
+        vec![9, 101, 109, 126]
+    );
+    assert!(referenced_payload_types(&[]).is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_rtcp_fb_for() -> Result<(), SdpParserInternalError> {
+    let attrs = vec![
+        SdpAttribute::from_str("rtpmap:126 H264/90000")?,
+        SdpAttribute::from_str("rtcp-fb:126 nack")?,
+        SdpAttribute::from_str("rtcp-fb:120 nack pli")?,
+        SdpAttribute::from_str("rtcp-fb:* ccm fir")?,
+    ];
+    let feedback = rtcp_fb_for(&attrs, 126);
+    assert_eq!(feedback.len(), 2);
+    assert_eq!(feedback[0].to_string(), "126 nack");
+    assert_eq!(feedback[1].to_string(), "* ccm fir");
+    let feedback = rtcp_fb_for(&attrs, 97);
+    assert_eq!(feedback.len(), 1);
+    assert_eq!(feedback[0].payload_type, SdpAttributePayloadType::Wildcard);
+    assert!(rtcp_fb_for(&[], 126).is_empty());
+    Ok(())
+}
@@ -0,0 +1,170 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+// Flat representations of the parsed attributes, made of strings and numbers
+// only. They are meant for crossing FFI or JSON boundaries, where consumers
+// should not be affected by changes to the (nested) attribute enums.
+
+use address::Address;
+use attribute_type::{
+    SdpAttribute, SdpAttributeCandidate, SdpAttributeCandidateTcpType,
+    SdpAttributeCandidateTransport, SdpAttributeCandidateType, SdpAttributeRtpmap,
+};
+use error::SdpParserInternalError;
+use std::convert::TryFrom;
+use std::net::IpAddr;
+use std::str::FromStr;
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct SdpAttributeDto {
+    pub name: String,
+    pub value: Option<String>,
+}
+
+impl From<&SdpAttribute> for SdpAttributeDto {
+    fn from(attr: &SdpAttribute) -> Self {
+        let attr_string = attr.to_string();
+        let mut tokens = attr_string.splitn(2, ':');
+        SdpAttributeDto {
+            name: tokens.next().unwrap_or_default().to_string(),
+            value: tokens.next().map(ToString::to_string),
+        }
+    }
+}
+
+impl TryFrom<&SdpAttributeDto> for SdpAttribute {
+    type Error = SdpParserInternalError;
+
+    fn try_from(dto: &SdpAttributeDto) -> Result<Self, Self::Error> {
+        match dto.value {
+            Some(ref value) => SdpAttribute::from_str(&format!("{}:{}", dto.name, value)),
+            None => SdpAttribute::from_str(&dto.name),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct SdpCandidateDto {
+    pub foundation: String,
+    pub component: u32,
+    pub transport: String,
+    pub priority: u32,
+    pub address: String,
+    pub port: u32,
+    pub candidate_type: String,
+    pub related_address: Option<String>,
+    pub related_port: Option<u32>,
+    pub tcp_type: Option<String>,
+    pub generation: Option<u32>,
+    pub ufrag: Option<String>,
+    pub network_cost: Option<u16>,
+    pub network_id: Option<u16>,
+    pub unknown_extensions: Vec<(String, String)>,
+    pub resolved_address: Option<String>,
+    pub scope: Option<String>,
+}
+
+impl From<&SdpAttributeCandidate> for SdpCandidateDto {
+    fn from(candidate: &SdpAttributeCandidate) -> Self {
+        SdpCandidateDto {
+            foundation: candidate.foundation.clone(),
+            component: candidate.component,
+            transport: candidate.transport.to_string(),
+            priority: candidate.priority,
+            address: candidate.address.to_string(),
+            port: candidate.port,
+            candidate_type: candidate.c_type.to_string(),
+            related_address: candidate.raddr.as_ref().map(ToString::to_string),
+            related_port: candidate.rport,
+            tcp_type: candidate.tcp_type.as_ref().map(ToString::to_string),
+            generation: candidate.generation,
+            ufrag: candidate.ufrag.clone(),
+            network_cost: candidate.networkcost,
+            network_id: candidate.networkid,
+            unknown_extensions: candidate.unknown_extensions.clone(),
+            resolved_address: candidate.resolved_address.map(|ip| ip.to_string()),
+            scope: candidate.scope.clone(),
+        }
+    }
+}
+
+impl TryFrom<&SdpCandidateDto> for SdpAttributeCandidate {
+    type Error = SdpParserInternalError;
+
+    fn try_from(dto: &SdpCandidateDto) -> Result<Self, Self::Error> {
+        // Transport extensions are kept, just like the lenient parser does
+        let transport = match SdpAttributeCandidateTransport::from_str(&dto.transport) {
+            Err(_) if !dto.transport.contains('/') => {
+                SdpAttributeCandidateTransport::Unknown(dto.transport.clone())
+            }
+            transport => transport?,
+        };
+        let mut candidate = SdpAttributeCandidate::new(
+            dto.foundation.clone(),
+            dto.component,
+            transport,
+            dto.priority,
+            Address::from_str(&dto.address)?,
+            dto.port,
+            SdpAttributeCandidateType::from_str(&dto.candidate_type)?,
+        );
+        candidate.raddr = match dto.related_address {
+            Some(ref addr) => Some(Address::from_str(addr)?),
+            None => None,
+        };
+        candidate.rport = dto.related_port;
+        candidate.tcp_type = match dto.tcp_type {
+            Some(ref tcp_type) => Some(SdpAttributeCandidateTcpType::from_str(tcp_type)?),
+            None => None,
+        };
+        candidate.generation = dto.generation;
+        candidate.ufrag = dto.ufrag.clone();
+        candidate.networkcost = dto.network_cost;
+        candidate.networkid = dto.network_id;
+        candidate.unknown_extensions = dto.unknown_extensions.clone();
+        candidate.resolved_address = match dto.resolved_address {
+            Some(ref ip) => Some(IpAddr::from_str(ip)?),
+            None => None,
+        };
+        candidate.scope = dto.scope.clone();
+        Ok(candidate)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct SdpRtpmapDto {
+    pub payload_type: u8,
+    pub codec_name: String,
+    pub frequency: Option<u32>,
+    pub channels: Option<u32>,
+}
+
+impl From<&SdpAttributeRtpmap> for SdpRtpmapDto {
+    fn from(rtpmap: &SdpAttributeRtpmap) -> Self {
+        SdpRtpmapDto {
+            payload_type: rtpmap.payload_type,
+            codec_name: rtpmap.codec_name.clone(),
+            frequency: rtpmap.frequency,
+            channels: rtpmap.channels,
+        }
+    }
+}
+
+impl From<&SdpRtpmapDto> for SdpAttributeRtpmap {
+    fn from(dto: &SdpRtpmapDto) -> Self {
+        SdpAttributeRtpmap {
+            payload_type: dto.payload_type,
+            codec_name: dto.codec_name.clone(),
+            frequency: dto.frequency,
+            channels: dto.channels,
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./dto_tests.rs"]
+mod tests;
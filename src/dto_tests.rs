@@ -0,0 +1,107 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use super::*;
+use attribute_type::parse_attribute;
+use SdpType;
+
+fn parse(attr_str: &str) -> SdpAttribute {
+    match parse_attribute(attr_str) {
+        Ok(SdpType::Attribute(attr)) => attr,
+        Err(e) => panic!("{}", e),
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn test_candidate_dto_round_trip() -> Result<(), SdpParserInternalError> {
+    let candidate_str = "candidate:1 1 TCP 1685987071 24.23.204.141 54609 typ srflx raddr 192.168.1.4 rport 61665 tcptype passive generation 1 ufrag +DGd network-cost 1 unsupported foo";
+    let candidate = match parse(candidate_str) {
+        SdpAttribute::Candidate(c) => c,
+        _ => unreachable!(),
+    };
+
+    let dto = SdpCandidateDto::from(&candidate);
+    assert_eq!(dto.transport, "TCP");
+    assert_eq!(dto.address, "24.23.204.141");
+    assert_eq!(dto.candidate_type, "srflx");
+    assert_eq!(dto.related_address, Some("192.168.1.4".to_string()));
+    assert_eq!(dto.tcp_type, Some("passive".to_string()));
+
+    let converted = SdpAttributeCandidate::try_from(&dto)?;
+    assert_eq!(
+        SdpAttribute::Candidate(converted).to_string(),
+        candidate_str
+    );
+    Ok(())
+}
+
+#[test]
+fn test_candidate_dto_unknown_transport() -> Result<(), SdpParserInternalError> {
+    let candidate_str = "candidate:0 1 DCCP 2122252543 172.16.156.106 49760 typ host";
+    let candidate = match parse(candidate_str) {
+        SdpAttribute::Candidate(c) => c,
+        _ => unreachable!(),
+    };
+
+    let dto = SdpCandidateDto::from(&candidate);
+    assert_eq!(dto.transport, "DCCP");
+    let converted = SdpAttributeCandidate::try_from(&dto)?;
+    assert!(converted == candidate);
+    assert_eq!(converted.to_ice_candidate().protocol, "dccp");
+    Ok(())
+}
+
+#[test]
+fn test_candidate_dto_invalid_values() {
+    let candidate = match parse("candidate:0 1 UDP 2122252543 172.16.156.106 49760 typ host") {
+        SdpAttribute::Candidate(c) => c,
+        _ => unreachable!(),
+    };
+    let dto = SdpCandidateDto::from(&candidate);
+
+    let mut broken_transport = dto.clone();
+    broken_transport.transport = "UDP/TLS".to_string();
+    assert!(SdpAttributeCandidate::try_from(&broken_transport).is_err());
+
+    let mut broken_type = dto;
+    broken_type.candidate_type = "fost".to_string();
+    assert!(SdpAttributeCandidate::try_from(&broken_type).is_err());
+}
+
+#[test]
+fn test_rtpmap_dto_round_trip() {
+    let rtpmap = match parse("rtpmap:109 opus/48000/2") {
+        SdpAttribute::Rtpmap(r) => r,
+        _ => unreachable!(),
+    };
+
+    let dto = SdpRtpmapDto::from(&rtpmap);
+    assert_eq!(
+        dto,
+        SdpRtpmapDto {
+            payload_type: 109,
+            codec_name: "opus".to_string(),
+            frequency: Some(48000),
+            channels: Some(2),
+        }
+    );
+    assert_eq!(
+        SdpAttribute::Rtpmap(SdpAttributeRtpmap::from(&dto)).to_string(),
+        "rtpmap:109 opus/48000/2"
+    );
+}
+
+#[test]
+fn test_attribute_dto_round_trip() -> Result<(), SdpParserInternalError> {
+    for attr_str in &["rtcp-mux", "mid:sdparta_0", "rtpmap:109 opus/48000/2"] {
+        let dto = SdpAttributeDto::from(&parse(attr_str));
+        assert_eq!(SdpAttribute::try_from(&dto)?.to_string(), *attr_str);
+    }
+
+    let flag = SdpAttributeDto::from(&SdpAttribute::RtcpMux);
+    assert_eq!(flag.name, "rtcp-mux");
+    assert_eq!(flag.value, None);
+    Ok(())
+}
@@ -12,6 +12,29 @@ use address::AddressType;
 use std::num::ParseFloatError;
 use std::num::ParseIntError;
 
+/// Coarse classification of parsing errors, allowing consumers to react to
+/// specific failures without having to match on the error messages.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+pub enum SdpErrorKind {
+    /// An attribute or value which is skipped, see SdpParserError::Unsupported.
+    Unsupported,
+    /// A valid SDP line type like e= or i=, which the parser rejects.
+    UnsupportedLineType,
+    UnknownAddressType,
+    AddressFamilyMismatch,
+    InvalidNumber,
+    InvalidAddress,
+    PortOutOfRange,
+    UnknownTransport,
+    MissingToken,
+    UnexpectedToken,
+    TokenCount,
+    InvalidValue,
+    InvalidLine,
+    Sequence,
+}
+
 #[derive(Debug, Clone)]
 pub enum SdpParserInternalError {
     UnknownAddressType(String),
@@ -20,6 +43,7 @@ pub enum SdpParserInternalError {
         expected: AddressType,
     },
     Generic(String),
+    Invalid(SdpErrorKind, String),
     Unsupported(String),
     Integer(ParseIntError),
     Float(ParseFloatError),
@@ -44,7 +68,10 @@ impl fmt::Display for SdpParserInternalError {
                 "{}: {}, {}",
                 INTERNAL_ERROR_MESSAGE_ADDRESS_TYPE_MISMATCH, found, expected
             ),
-            SdpParserInternalError::Generic(ref message) => write!(f, "Parsing error: {}", message),
+            SdpParserInternalError::Generic(ref message)
+            | SdpParserInternalError::Invalid(_, ref message) => {
+                write!(f, "Parsing error: {}", message)
+            }
             SdpParserInternalError::Unsupported(ref message) => {
                 write!(f, "Unsupported parsing error: {}", message)
             }
@@ -62,6 +89,27 @@ impl fmt::Display for SdpParserInternalError {
     }
 }
 
+impl SdpParserInternalError {
+    pub fn kind(&self) -> SdpErrorKind {
+        match *self {
+            SdpParserInternalError::UnknownAddressType(_) => SdpErrorKind::UnknownAddressType,
+            SdpParserInternalError::AddressTypeMismatch { .. } => {
+                SdpErrorKind::AddressFamilyMismatch
+            }
+            // The parser itself no longer reports Generic errors
+            SdpParserInternalError::Generic(_) => SdpErrorKind::InvalidValue,
+            SdpParserInternalError::Invalid(kind, _) => kind,
+            SdpParserInternalError::Unsupported(_) => SdpErrorKind::Unsupported,
+            SdpParserInternalError::Integer(_) | SdpParserInternalError::Float(_) => {
+                SdpErrorKind::InvalidNumber
+            }
+            SdpParserInternalError::Domain(_) | SdpParserInternalError::IpAddress(_) => {
+                SdpErrorKind::InvalidAddress
+            }
+        }
+    }
+}
+
 impl Error for SdpParserInternalError {
     fn source(&self) -> Option<&(dyn error::Error + 'static)> {
         match *self {
@@ -171,6 +219,16 @@ impl fmt::Display for SdpParserError {
     }
 }
 
+impl SdpParserError {
+    pub fn kind(&self) -> SdpErrorKind {
+        match *self {
+            SdpParserError::Line { ref error, .. }
+            | SdpParserError::Unsupported { ref error, .. } => error.kind(),
+            SdpParserError::Sequence { .. } => SdpErrorKind::Sequence,
+        }
+    }
+}
+
 impl Error for SdpParserError {
     fn source(&self) -> Option<&(dyn error::Error + 'static)> {
         match *self {
@@ -182,6 +240,139 @@ impl Error for SdpParserError {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+pub enum SdpWarningKind {
+    Deprecated,
+    LegacySyntax,
+    UnknownExtension,
+}
+
+/// Non fatal issue found while parsing, which did not prevent the parser
+/// from producing a result.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+pub struct SdpWarning {
+    pub kind: SdpWarningKind,
+    pub message: String,
+}
+
+impl SdpWarning {
+    pub fn new(kind: SdpWarningKind, message: &str) -> SdpWarning {
+        SdpWarning {
+            kind,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for SdpWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Warning: {}", self.message)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+pub enum SdpSeverity {
+    Error,
+    Warning,
+    Info,
+}
+
+impl fmt::Display for SdpSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SdpSeverity::Error => "Error",
+            SdpSeverity::Warning => "Warning",
+            SdpSeverity::Info => "Info",
+        }
+        .fmt(f)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+pub enum SdpDiagnosticKind {
+    Error(SdpErrorKind),
+    Warning(SdpWarningKind),
+}
+
+/// Issue found in a single line together with its severity. Syntax errors
+/// are errors, deprecated attributes or legacy syntax are warnings and
+/// unsupported attributes are only informational.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+pub struct SdpDiagnostic {
+    pub severity: SdpSeverity,
+    pub kind: SdpDiagnosticKind,
+    pub message: String,
+    pub line: String,
+    pub line_number: usize,
+}
+
+impl SdpDiagnostic {
+    pub fn from_warning(warning: SdpWarning, line: &str, line_number: usize) -> SdpDiagnostic {
+        SdpDiagnostic {
+            severity: SdpSeverity::Warning,
+            kind: SdpDiagnosticKind::Warning(warning.kind),
+            message: warning.message,
+            line: line.to_string(),
+            line_number,
+        }
+    }
+}
+
+impl From<SdpParserError> for SdpDiagnostic {
+    fn from(err: SdpParserError) -> SdpDiagnostic {
+        let kind = SdpDiagnosticKind::Error(err.kind());
+        match err {
+            SdpParserError::Line {
+                error,
+                line,
+                line_number,
+            } => SdpDiagnostic {
+                severity: SdpSeverity::Error,
+                kind,
+                message: error.to_string(),
+                line,
+                line_number,
+            },
+            SdpParserError::Unsupported {
+                error,
+                line,
+                line_number,
+            } => SdpDiagnostic {
+                severity: SdpSeverity::Info,
+                kind,
+                message: error.to_string(),
+                line,
+                line_number,
+            },
+            SdpParserError::Sequence {
+                message,
+                line_number,
+            } => SdpDiagnostic {
+                severity: SdpSeverity::Error,
+                kind,
+                message,
+                line: "".to_string(),
+                line_number,
+            },
+        }
+    }
+}
+
+impl fmt::Display for SdpDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} in line({}): {}",
+            self.severity, self.message, self.line_number, self.line
+        )
+    }
+}
+
 impl From<ParseIntError> for SdpParserInternalError {
     fn from(err: ParseIntError) -> SdpParserInternalError {
         SdpParserInternalError::Integer(err)
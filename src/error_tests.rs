@@ -127,3 +127,45 @@ fn test_sdp_parser_error_sequence() {
     );
     assert!(sequence1.source().is_none());
 }
+
+#[test]
+fn test_sdp_parser_internal_error_invalid() {
+    let invalid = SdpParserInternalError::Invalid(
+        SdpErrorKind::PortOutOfRange,
+        "port is too big".to_string(),
+    );
+    assert_eq!(format!("{}", invalid), "Parsing error: port is too big");
+    assert_eq!(invalid.kind(), SdpErrorKind::PortOutOfRange);
+    assert!(invalid.source().is_none());
+}
+
+#[test]
+fn test_sdp_parser_error_kind() {
+    assert_eq!(
+        SdpParserInternalError::Generic("generic".to_string()).kind(),
+        SdpErrorKind::InvalidValue
+    );
+    assert_eq!(
+        SdpParserInternalError::UnknownAddressType("foo".to_string()).kind(),
+        SdpErrorKind::UnknownAddressType
+    );
+    assert_eq!(
+        SdpParserInternalError::from("12a".parse::<u32>().unwrap_err()).kind(),
+        SdpErrorKind::InvalidNumber
+    );
+
+    let line = SdpParserError::Line {
+        error: SdpParserInternalError::Invalid(
+            SdpErrorKind::MissingToken,
+            "missing token".to_string(),
+        ),
+        line: "test line".to_string(),
+        line_number: 13,
+    };
+    assert_eq!(line.kind(), SdpErrorKind::MissingToken);
+    let sequence = SdpParserError::Sequence {
+        message: "sequence message".to_string(),
+        line_number: 42,
+    };
+    assert_eq!(sequence.kind(), SdpErrorKind::Sequence);
+}
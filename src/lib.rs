@@ -14,28 +14,78 @@ extern crate serde_derive;
 extern crate serde;
 use std::convert::TryFrom;
 use std::fmt;
+use std::net::IpAddr;
 
 #[macro_use]
 pub mod attribute_type;
 pub mod address;
 pub mod anonymizer;
+pub mod dto;
 pub mod error;
 pub mod media_type;
 pub mod network;
 
+/// Single import point for the attribute value types and the address types
+/// they are built from.
+///
+/// ```
+/// use webrtc_sdp::attributes::{SdpAttribute, SdpAttributeRtpmap};
+///
+/// let rtpmap = SdpAttributeRtpmap::new(109, "opus".to_string(), 48000);
+/// let attribute = SdpAttribute::Rtpmap(rtpmap);
+/// assert_eq!(attribute.to_string(), "rtpmap:109 opus/48000");
+/// ```
+pub mod attributes {
+    pub use address::{Address, AddressType, ExplicitlyTypedAddress};
+    pub use attribute_type::{
+        IceCandidate, MsidId, RtxFmtpParameters, SdpAttribute, SdpAttributeCandidate,
+        SdpAttributeCandidateTcpType, SdpAttributeCandidateTransport, SdpAttributeCandidateType,
+        SdpAttributeDirection, SdpAttributeDtlsMessage, SdpAttributeExtmap,
+        SdpAttributeFingerprint, SdpAttributeFingerprintHashType, SdpAttributeFmtp,
+        SdpAttributeFmtpParameters, SdpAttributeGroup, SdpAttributeGroupSemantic,
+        SdpAttributeImageAttr, SdpAttributeImageAttrPRange, SdpAttributeImageAttrSRange,
+        SdpAttributeImageAttrSet, SdpAttributeImageAttrSetList, SdpAttributeImageAttrXyRange,
+        SdpAttributeMediaClk, SdpAttributeMsid, SdpAttributeMsidSemantic, SdpAttributePayloadType,
+        SdpAttributeRemoteCandidate, SdpAttributeRid, SdpAttributeRidParameters, SdpAttributeRtcp,
+        SdpAttributeRtcpFb, SdpAttributeRtcpFbType, SdpAttributeRtpmap, SdpAttributeSctpmap,
+        SdpAttributeSetup, SdpAttributeSimulcast, SdpAttributeSimulcastId,
+        SdpAttributeSimulcastVersion, SdpAttributeSsrc, SdpAttributeTsRefclk, SdpAttributeType,
+        SdpIceOption, SdpRtpHeaderExtension, SdpSingleDirection, SdpSsrcGroupSemantic,
+    };
+}
+
 use address::{AddressTyped, ExplicitlyTypedAddress};
 use anonymizer::{AnonymizingClone, StatefulSdpAnonymizer};
 use attribute_type::{
-    parse_attribute, SdpAttribute, SdpAttributeRid, SdpAttributeSimulcastVersion, SdpAttributeType,
-    SdpSingleDirection,
+    parse_attribute_with_config, SdpAttribute, SdpAttributeRid, SdpAttributeSetup,
+    SdpAttributeSimulcastVersion, SdpAttributeType, SdpSingleDirection,
+};
+use error::{
+    SdpDiagnostic, SdpErrorKind, SdpParserError, SdpParserInternalError, SdpSeverity, SdpWarning,
+    SdpWarningKind,
 };
-use error::{SdpParserError, SdpParserInternalError};
 use media_type::{
     parse_media, parse_media_vector, SdpFormatList, SdpMedia, SdpMediaLine, SdpMediaValue,
     SdpProtocolValue,
 };
 use network::{parse_address_type, parse_network_type};
 
+/// Options which influence how an SDP string gets parsed.
+#[derive(Clone, Default)]
+pub struct SdpParserConfig {
+    /// Invoked for every candidate address which is a host name (for example
+    /// an mDNS `.local` name). If it returns an IP address, the candidate keeps
+    /// the host name as its address and additionally carries the resolved IP.
+    pub hostname_resolver: Option<fn(&str) -> Option<IpAddr>>,
+    /// Rejects input which the default parser tolerates, like unexpected
+    /// trailing tokens in attributes with a fixed number of tokens.
+    pub strict: bool,
+    /// Keeps the exact text of every attribute line, see SdpLine::original,
+    /// so that serializing the session re-emits unchanged attributes
+    /// byte-identical instead of in their canonical form.
+    pub preserve_original: bool,
+}
+
 /*
  * RFC4566
  * bandwidth-fields =    *(%x62 "=" bwtype ":" bandwidth CRLF)
@@ -170,6 +220,10 @@ pub struct SdpLine {
     pub line_number: usize,
     pub sdp_type: SdpType,
     pub text: String,
+    // The attribute line exactly as it was given, including the "a=" and
+    // any surrounding whitespace. Only recorded with
+    // SdpParserConfig::preserve_original
+    pub original: Option<String>,
 }
 
 /*
@@ -200,6 +254,11 @@ pub struct SdpSession {
     pub bandwidth: Vec<SdpBandwidth>,
     pub timing: Option<SdpTiming>,
     pub attribute: Vec<SdpAttribute>,
+    // The original line of the attribute at the same index, together with
+    // the attribute as it was parsed. Only filled with
+    // SdpParserConfig::preserve_original
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    originals: SdpOriginals,
     pub media: Vec<SdpMedia>,
     pub warnings: Vec<SdpParserError>, // unsupported values:
                                        // information: Option<String>,
@@ -229,7 +288,7 @@ impl fmt::Display for SdpSession {
             timing = option_to_string!("t={}\r\n", self.timing),
             bandwidth = maybe_vector_to_string!("b={}\r\n", self.bandwidth, "\r\nb="),
             connection = option_to_string!("c={}\r\n", self.connection),
-            session_attributes = maybe_vector_to_string!("a={}\r\n", self.attribute, "\r\na="),
+            session_attributes = attributes_to_sdp_preserving(&self.attribute, &self.originals),
             media_sections = self.media.iter().map(|s| s.to_string()).collect::<String>(),
         )
     }
@@ -249,6 +308,7 @@ impl SdpSession {
             bandwidth: Vec::new(),
             timing: None,
             attribute: Vec::new(),
+            originals: Vec::new(),
             media: Vec::new(),
             warnings: Vec::new(),
         }
@@ -291,10 +351,10 @@ impl SdpSession {
 
     pub fn add_attribute(&mut self, a: SdpAttribute) -> Result<(), SdpParserInternalError> {
         if !a.allowed_at_session_level() {
-            return Err(SdpParserInternalError::Generic(format!(
-                "{} not allowed at session level",
-                a
-            )));
+            return Err(SdpParserInternalError::Invalid(
+                SdpErrorKind::Sequence,
+                format!("{} not allowed at session level", a),
+            ));
         };
         self.attribute.push(a);
         Ok(())
@@ -315,7 +375,10 @@ impl SdpSession {
                             message: format!("{}", e),
                             line_number: _line_number,
                         }
-                    })?
+                    })?;
+                    if let Some(original) = line.original {
+                        record_original(&self.attribute, &mut self.originals, original);
+                    }
                 }
                 SdpType::Bandwidth(b) => self.add_bandwidth(b),
                 SdpType::Timing(t) => self.set_timing(t),
@@ -344,6 +407,13 @@ impl SdpSession {
             .find(|a| SdpAttributeType::from(*a) == t)
     }
 
+    /// The original line of the session level attribute at the given index,
+    /// if it got parsed with SdpParserConfig::preserve_original and has not
+    /// been changed or moved since.
+    pub fn get_original(&self, index: usize) -> Option<&str> {
+        original_at(&self.attribute, &self.originals, index)
+    }
+
     pub fn add_media(
         &mut self,
         media_type: SdpMediaValue,
@@ -384,6 +454,8 @@ impl AnonymizingClone for SdpSession {
             timing: self.timing.clone(),
             bandwidth: self.bandwidth.clone(),
             attribute: Vec::new(),
+            // The original text would leak the unmasked values
+            originals: Vec::new(),
             media: Vec::new(),
             warnings: Vec::new(),
         };
@@ -396,6 +468,46 @@ impl AnonymizingClone for SdpSession {
     }
 }
 
+// The original lines of the attributes of a section, index by index, each
+// together with the attribute as it was parsed. Missing trailing entries
+// mean that there is no original line.
+type SdpOriginals = Vec<Option<(SdpAttribute, String)>>;
+
+// Records the original line of the attribute which was added last.
+fn record_original(attrs: &[SdpAttribute], originals: &mut SdpOriginals, original: String) {
+    if let Some((attr, rest)) = attrs.split_last() {
+        originals.resize(rest.len(), None);
+        originals.push(Some((attr.clone(), original)));
+    }
+}
+
+// The original line of the attribute at the given index, unless the
+// attribute got changed or moved since it was parsed.
+fn original_at<'a>(
+    attrs: &[SdpAttribute],
+    originals: &'a [Option<(SdpAttribute, String)>],
+    index: usize,
+) -> Option<&'a str> {
+    match (attrs.get(index), originals.get(index)) {
+        (Some(attr), Some(Some((parsed, original)))) if attr == parsed => Some(original),
+        _ => None,
+    }
+}
+
+// Serializes the attributes as "a=" lines, using the original line instead
+// for every attribute which still has one.
+fn attributes_to_sdp_preserving(
+    attrs: &[SdpAttribute],
+    originals: &[Option<(SdpAttribute, String)>],
+) -> String {
+    (0..attrs.len())
+        .map(|i| match original_at(attrs, originals, i) {
+            Some(original) => format!("{}\r\n", original),
+            None => format!("a={}\r\n", attrs[i]),
+        })
+        .collect()
+}
+
 /* removing this wrap would not allow us to call this from the match statement inside
  * parse_sdp_line() */
 #[allow(clippy::unnecessary_wraps)]
@@ -407,10 +519,10 @@ fn parse_session(value: &str) -> Result<SdpType, SdpParserInternalError> {
 fn parse_version(value: &str) -> Result<SdpType, SdpParserInternalError> {
     let ver = value.parse::<u64>()?;
     if ver != 0 {
-        return Err(SdpParserInternalError::Generic(format!(
-            "version type contains unsupported value {}",
-            ver
-        )));
+        return Err(SdpParserInternalError::Invalid(
+            SdpErrorKind::InvalidValue,
+            format!("version type contains unsupported value {}", ver),
+        ));
     };
     trace!("version: {}", ver);
     Ok(SdpType::Version(ver))
@@ -420,7 +532,8 @@ fn parse_origin(value: &str) -> Result<SdpType, SdpParserInternalError> {
     let mut tokens = value.split_whitespace();
     let username = match tokens.next() {
         None => {
-            return Err(SdpParserInternalError::Generic(
+            return Err(SdpParserInternalError::Invalid(
+                SdpErrorKind::MissingToken,
                 "Origin type is missing username token".to_string(),
             ));
         }
@@ -428,7 +541,8 @@ fn parse_origin(value: &str) -> Result<SdpType, SdpParserInternalError> {
     };
     let session_id = match tokens.next() {
         None => {
-            return Err(SdpParserInternalError::Generic(
+            return Err(SdpParserInternalError::Invalid(
+                SdpErrorKind::MissingToken,
                 "Origin type is missing session ID token".to_string(),
             ));
         }
@@ -436,7 +550,8 @@ fn parse_origin(value: &str) -> Result<SdpType, SdpParserInternalError> {
     };
     let session_version = match tokens.next() {
         None => {
-            return Err(SdpParserInternalError::Generic(
+            return Err(SdpParserInternalError::Invalid(
+                SdpErrorKind::MissingToken,
                 "Origin type is missing session version token".to_string(),
             ));
         }
@@ -444,7 +559,8 @@ fn parse_origin(value: &str) -> Result<SdpType, SdpParserInternalError> {
     };
     match tokens.next() {
         None => {
-            return Err(SdpParserInternalError::Generic(
+            return Err(SdpParserInternalError::Invalid(
+                SdpErrorKind::MissingToken,
                 "Origin type is missing network type token".to_string(),
             ));
         }
@@ -452,7 +568,8 @@ fn parse_origin(value: &str) -> Result<SdpType, SdpParserInternalError> {
     };
     let addrtype = match tokens.next() {
         None => {
-            return Err(SdpParserInternalError::Generic(
+            return Err(SdpParserInternalError::Invalid(
+                SdpErrorKind::MissingToken,
                 "Origin type is missing address type token".to_string(),
             ));
         }
@@ -460,14 +577,16 @@ fn parse_origin(value: &str) -> Result<SdpType, SdpParserInternalError> {
     };
     let unicast_addr = match tokens.next() {
         None => {
-            return Err(SdpParserInternalError::Generic(
+            return Err(SdpParserInternalError::Invalid(
+                SdpErrorKind::MissingToken,
                 "Origin type is missing IP address token".to_string(),
             ));
         }
         Some(x) => ExplicitlyTypedAddress::try_from((addrtype, x))?,
     };
     if addrtype != unicast_addr.address_type() {
-        return Err(SdpParserInternalError::Generic(
+        return Err(SdpParserInternalError::Invalid(
+            SdpErrorKind::AddressFamilyMismatch,
             "Origin addrtype does not match address.".to_string(),
         ));
     }
@@ -484,7 +603,8 @@ fn parse_origin(value: &str) -> Result<SdpType, SdpParserInternalError> {
 fn parse_connection(value: &str) -> Result<SdpType, SdpParserInternalError> {
     let cv: Vec<&str> = value.split_whitespace().collect();
     if cv.len() != 3 {
-        return Err(SdpParserInternalError::Generic(
+        return Err(SdpParserInternalError::Invalid(
+            SdpErrorKind::TokenCount,
             "connection attribute must have three tokens".to_string(),
         ));
     }
@@ -514,7 +634,8 @@ fn parse_connection(value: &str) -> Result<SdpType, SdpParserInternalError> {
 fn parse_bandwidth(value: &str) -> Result<SdpType, SdpParserInternalError> {
     let bv: Vec<&str> = value.split(':').collect();
     if bv.len() != 2 {
-        return Err(SdpParserInternalError::Generic(
+        return Err(SdpParserInternalError::Invalid(
+            SdpErrorKind::TokenCount,
             "bandwidth attribute must have two tokens".to_string(),
         ));
     }
@@ -532,7 +653,8 @@ fn parse_bandwidth(value: &str) -> Result<SdpType, SdpParserInternalError> {
 fn parse_timing(value: &str) -> Result<SdpType, SdpParserInternalError> {
     let tv: Vec<&str> = value.split_whitespace().collect();
     if tv.len() != 2 {
-        return Err(SdpParserInternalError::Generic(
+        return Err(SdpParserInternalError::Invalid(
+            SdpErrorKind::TokenCount,
             "timing attribute must have two tokens".to_string(),
         ));
     }
@@ -543,10 +665,23 @@ fn parse_timing(value: &str) -> Result<SdpType, SdpParserInternalError> {
     Ok(SdpType::Timing(t))
 }
 
+// Only the tests parse single lines without a config.
+#[cfg(test)]
 fn parse_sdp_line(line: &str, line_number: usize) -> Result<SdpLine, SdpParserError> {
+    parse_sdp_line_with_config(line, line_number, &SdpParserConfig::default())
+}
+
+fn parse_sdp_line_with_config(
+    line: &str,
+    line_number: usize,
+    config: &SdpParserConfig,
+) -> Result<SdpLine, SdpParserError> {
     if line.find('=') == None {
         return Err(SdpParserError::Line {
-            error: SdpParserInternalError::Generic("missing = character in line".to_string()),
+            error: SdpParserInternalError::Invalid(
+                SdpErrorKind::InvalidLine,
+                "missing = character in line".to_string(),
+            ),
             line: line.to_string(),
             line_number,
         });
@@ -555,7 +690,10 @@ fn parse_sdp_line(line: &str, line_number: usize) -> Result<SdpLine, SdpParserEr
     let line_type = match splitted_line.next() {
         None => {
             return Err(SdpParserError::Line {
-                error: SdpParserInternalError::Generic("missing type".to_string()),
+                error: SdpParserInternalError::Invalid(
+                    SdpErrorKind::InvalidLine,
+                    "missing type".to_string(),
+                ),
                 line: line.to_string(),
                 line_number,
             });
@@ -564,14 +702,20 @@ fn parse_sdp_line(line: &str, line_number: usize) -> Result<SdpLine, SdpParserEr
             let trimmed = t.trim();
             if trimmed.len() > 1 {
                 return Err(SdpParserError::Line {
-                    error: SdpParserInternalError::Generic("type too long".to_string()),
+                    error: SdpParserInternalError::Invalid(
+                        SdpErrorKind::InvalidLine,
+                        "type too long".to_string(),
+                    ),
                     line: line.to_string(),
                     line_number,
                 });
             }
             if trimmed.is_empty() {
                 return Err(SdpParserError::Line {
-                    error: SdpParserInternalError::Generic("type is empty".to_string()),
+                    error: SdpParserInternalError::Invalid(
+                        SdpErrorKind::InvalidLine,
+                        "type is empty".to_string(),
+                    ),
                     line: line.to_string(),
                     line_number,
                 });
@@ -582,7 +726,10 @@ fn parse_sdp_line(line: &str, line_number: usize) -> Result<SdpLine, SdpParserEr
     let (line_value, untrimmed_line_value) = match splitted_line.next() {
         None => {
             return Err(SdpParserError::Line {
-                error: SdpParserInternalError::Generic("missing value".to_string()),
+                error: SdpParserInternalError::Invalid(
+                    SdpErrorKind::InvalidLine,
+                    "missing value".to_string(),
+                ),
                 line: line.to_string(),
                 line_number,
             });
@@ -592,7 +739,10 @@ fn parse_sdp_line(line: &str, line_number: usize) -> Result<SdpLine, SdpParserEr
             // For compatibility with sites that don't adhere to "s=-" for no session ID
             if trimmed.is_empty() && line_type.as_str() != "s" {
                 return Err(SdpParserError::Line {
-                    error: SdpParserInternalError::Generic("value is empty".to_string()),
+                    error: SdpParserInternalError::Invalid(
+                        SdpErrorKind::InvalidLine,
+                        "value is empty".to_string(),
+                    ),
                     line: line.to_string(),
                     line_number,
                 });
@@ -601,55 +751,65 @@ fn parse_sdp_line(line: &str, line_number: usize) -> Result<SdpLine, SdpParserEr
         }
     };
     match line_type.as_ref() {
-        "a" => parse_attribute(line_value),
+        "a" => parse_attribute_with_config(line_value, config),
         "b" => parse_bandwidth(line_value),
         "c" => parse_connection(line_value),
-        "e" => Err(SdpParserInternalError::Generic(format!(
-            "unsupported type email: {}",
-            line_value
-        ))),
-        "i" => Err(SdpParserInternalError::Generic(format!(
-            "unsupported type information: {}",
-            line_value
-        ))),
-        "k" => Err(SdpParserInternalError::Generic(format!(
-            "unsupported insecure key exchange: {}",
-            line_value
-        ))),
+        "e" => Err(SdpParserInternalError::Invalid(
+            SdpErrorKind::UnsupportedLineType,
+            format!("unsupported type email: {}", line_value),
+        )),
+        "i" => Err(SdpParserInternalError::Invalid(
+            SdpErrorKind::UnsupportedLineType,
+            format!("unsupported type information: {}", line_value),
+        )),
+        "k" => Err(SdpParserInternalError::Invalid(
+            SdpErrorKind::UnsupportedLineType,
+            format!("unsupported insecure key exchange: {}", line_value),
+        )),
         "m" => parse_media(line_value),
         "o" => parse_origin(line_value),
-        "p" => Err(SdpParserInternalError::Generic(format!(
-            "unsupported type phone: {}",
-            line_value
-        ))),
-        "r" => Err(SdpParserInternalError::Generic(format!(
-            "unsupported type repeat: {}",
-            line_value
-        ))),
+        "p" => Err(SdpParserInternalError::Invalid(
+            SdpErrorKind::UnsupportedLineType,
+            format!("unsupported type phone: {}", line_value),
+        )),
+        "r" => Err(SdpParserInternalError::Invalid(
+            SdpErrorKind::UnsupportedLineType,
+            format!("unsupported type repeat: {}", line_value),
+        )),
         "s" => parse_session(untrimmed_line_value),
         "t" => parse_timing(line_value),
-        "u" => Err(SdpParserInternalError::Generic(format!(
-            "unsupported type uri: {}",
-            line_value
-        ))),
+        "u" => Err(SdpParserInternalError::Invalid(
+            SdpErrorKind::UnsupportedLineType,
+            format!("unsupported type uri: {}", line_value),
+        )),
         "v" => parse_version(line_value),
-        "z" => Err(SdpParserInternalError::Generic(format!(
-            "unsupported type zone: {}",
-            line_value
-        ))),
-        _ => Err(SdpParserInternalError::Generic(
+        "z" => Err(SdpParserInternalError::Invalid(
+            SdpErrorKind::UnsupportedLineType,
+            format!("unsupported type zone: {}", line_value),
+        )),
+        _ => Err(SdpParserInternalError::Invalid(
+            SdpErrorKind::InvalidLine,
             "unknown sdp type".to_string(),
         )),
     }
     .map(|sdp_type| SdpLine {
+        original: match sdp_type {
+            SdpType::Attribute(_) if config.preserve_original => Some(line.to_string()),
+            _ => None,
+        },
         line_number,
         sdp_type,
         text: line.to_owned(),
     })
-    .map_err(|e| match e {
+    .map_err(|e| to_line_error(e, line, line_number))
+}
+
+fn to_line_error(e: SdpParserInternalError, line: &str, line_number: usize) -> SdpParserError {
+    match e {
         SdpParserInternalError::UnknownAddressType(..)
         | SdpParserInternalError::AddressTypeMismatch { .. }
         | SdpParserInternalError::Generic(..)
+        | SdpParserInternalError::Invalid(..)
         | SdpParserInternalError::Integer(..)
         | SdpParserInternalError::Float(..)
         | SdpParserInternalError::Domain(..)
@@ -663,7 +823,150 @@ fn parse_sdp_line(line: &str, line_number: usize) -> Result<SdpLine, SdpParserEr
             line: line.to_string(),
             line_number,
         },
-    })
+    }
+}
+
+/// Reports every attribute type which appears more often within the given
+/// section attributes than it is allowed to. The direction attributes
+/// sendrecv, sendonly, recvonly and inactive count as one type. Each error
+/// points at the first attribute exceeding the limit, the line numbers of
+/// the errors are the indices into the given attributes.
+pub fn check_cardinality(attrs: &[SdpAttribute]) -> Vec<SdpParserError> {
+    let mut seen: Vec<SdpAttributeType> = Vec::new();
+    let mut errors: Vec<(SdpAttributeType, SdpParserError)> = Vec::new();
+    for (line_number, attr) in attrs.iter().enumerate() {
+        let attr_type = match SdpAttributeType::from(attr) {
+            SdpAttributeType::Sendonly
+            | SdpAttributeType::Recvonly
+            | SdpAttributeType::Inactive => SdpAttributeType::Sendrecv,
+            attr_type => attr_type,
+        };
+        if attr_type.allows_multiple() || errors.iter().any(|(t, _)| *t == attr_type) {
+            continue;
+        }
+        if !seen.contains(&attr_type) {
+            seen.push(attr_type);
+            continue;
+        }
+        let message = match attr_type {
+            SdpAttributeType::Sendrecv => "Only one direction attribute is allowed".to_string(),
+            _ => format!("Attribute {} must not appear more than once", attr_type),
+        };
+        let error = to_line_error(
+            SdpParserInternalError::Invalid(SdpErrorKind::Sequence, message),
+            &attr.to_string(),
+            line_number,
+        );
+        errors.push((attr_type, error));
+    }
+    errors.into_iter().map(|(_, error)| error).collect()
+}
+
+/// Differences between two attribute lists of the same section.
+#[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+#[cfg_attr(feature = "enhanced_debug", derive(Debug))]
+pub struct SdpAttributeDiff {
+    pub added: Vec<SdpAttribute>,
+    pub removed: Vec<SdpAttribute>,
+    /// Pairs of the old and the new version of an attribute.
+    pub changed: Vec<(SdpAttribute, SdpAttribute)>,
+}
+
+impl SdpAttributeDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+// Identifies an attribute across two versions of a section: attributes
+// which may only appear once are identified by their type, the others by
+// their identifying field, or by their whole value if they have none.
+fn attribute_diff_key(attr: &SdpAttribute) -> String {
+    let attr_type = SdpAttributeType::from(attr);
+    match *attr {
+        SdpAttribute::Inactive
+        | SdpAttribute::Recvonly
+        | SdpAttribute::Sendonly
+        | SdpAttribute::Sendrecv => "direction".to_string(),
+        SdpAttribute::Candidate(ref c) => {
+            format!("{} {} {}", attr_type, c.foundation, c.component)
+        }
+        SdpAttribute::Extmap(ref extmap) => format!("{} {}", attr_type, extmap.id),
+        SdpAttribute::Fmtp(ref fmtp) => format!("{} {}", attr_type, fmtp.payload_type),
+        SdpAttribute::Rid(ref rid) => format!("{} {}", attr_type, rid.id),
+        SdpAttribute::Rtpmap(ref rtpmap) => format!("{} {}", attr_type, rtpmap.payload_type),
+        SdpAttribute::Ssrc(ref ssrc) => format!(
+            "{} {} {}",
+            attr_type,
+            ssrc.id,
+            ssrc.attribute.as_deref().unwrap_or("")
+        ),
+        _ if attr_type.allows_multiple() => attr.to_string(),
+        _ => attr_type.to_string(),
+    }
+}
+
+/// Compares two versions of the attributes of a section, as needed for
+/// renegotiation. Attributes are matched by their type and identifying
+/// field, like the payload type of rtpmap and fmtp or the foundation and
+/// component of candidates. Direction attributes are matched with each
+/// other, so that switching the direction shows up as a change.
+pub fn diff_attributes(old: &[SdpAttribute], new: &[SdpAttribute]) -> SdpAttributeDiff {
+    let mut unmatched: Vec<(String, &SdpAttribute)> =
+        new.iter().map(|a| (attribute_diff_key(a), a)).collect();
+    let mut diff = SdpAttributeDiff {
+        added: Vec::new(),
+        removed: Vec::new(),
+        changed: Vec::new(),
+    };
+    for old_attr in old {
+        let key = attribute_diff_key(old_attr);
+        match unmatched.iter().position(|(k, _)| *k == key) {
+            Some(index) => {
+                let (_, new_attr) = unmatched.remove(index);
+                if new_attr != old_attr {
+                    diff.changed.push((old_attr.clone(), new_attr.clone()));
+                }
+            }
+            None => diff.removed.push(old_attr.clone()),
+        }
+    }
+    diff.added = unmatched.into_iter().map(|(_, a)| a.clone()).collect();
+    diff
+}
+
+/// Reports every extmap of the section which needs the two-byte RTP header
+/// format without extmap-allow-mixed being present at session or media
+/// level, in which case the one-byte format would have to be used. The
+/// attributes carry no line numbers, so the errors do not have any either.
+pub fn check_extmap_ids(
+    session_attrs: &[SdpAttribute],
+    media_attrs: &[SdpAttribute],
+) -> Vec<SdpParserInternalError> {
+    if session_attrs
+        .iter()
+        .chain(media_attrs)
+        .any(|a| a.is_kind(&SdpAttributeType::ExtmapAllowMixed))
+    {
+        return Vec::new();
+    }
+    session_attrs
+        .iter()
+        .chain(media_attrs)
+        .filter_map(|a| match *a {
+            SdpAttribute::Extmap(ref extmap) if extmap.requires_two_byte_header() => {
+                Some(SdpParserInternalError::Invalid(
+                    SdpErrorKind::Sequence,
+                    format!(
+                        "Extmap id {} requires extmap-allow-mixed to be present",
+                        extmap.id
+                    ),
+                ))
+            }
+            _ => None,
+        })
+        .collect()
 }
 
 fn sanity_check_sdp_session(session: &SdpSession) -> Result<(), SdpParserError> {
@@ -841,10 +1144,250 @@ fn parse_sdp_vector(lines: &mut Vec<SdpLine>) -> Result<SdpSession, SdpParserErr
     Ok(sdp_session)
 }
 
+/// Splits SDP into lines. RFC 4566 mandates CRLF, but bare LF line endings
+/// are accepted as well. Unlike str::lines() this also strips repeated CRs
+/// at the end of a line.
+fn split_lines(input: &str) -> impl Iterator<Item = &str> {
+    input.lines().map(|line| line.trim_end_matches('\r'))
+}
+
+/// Groups the values of the attribute lines of a raw SDP by the section they
+/// belong to. The first element holds the session level attributes, the
+/// second one the attributes of every m-section in order. The values do not
+/// contain the leading "a=" and can be handed to parse_attribute directly.
+pub fn split_attribute_blocks(sdp: &str) -> (Vec<&str>, Vec<Vec<&str>>) {
+    let mut session = Vec::new();
+    let mut media: Vec<Vec<&str>> = Vec::new();
+    for line in split_lines(sdp).map(str::trim) {
+        if line.starts_with("m=") {
+            media.push(Vec::new());
+        } else if let Some(value) = line.strip_prefix("a=") {
+            let value = value.trim();
+            match media.last_mut() {
+                Some(block) => block.push(value),
+                None => session.push(value),
+            }
+        }
+    }
+    (session, media)
+}
+
+/// Lazily parses the attribute lines of a raw SDP, yielding one result per
+/// "a=" line. All other lines are skipped.
+pub fn iter_attributes(
+    sdp: &str,
+) -> impl Iterator<Item = Result<SdpAttribute, SdpParserError>> + '_ {
+    split_lines(sdp)
+        .enumerate()
+        .filter_map(|(line_number, line)| {
+            let value = line.trim().strip_prefix("a=")?;
+            Some(
+                value
+                    .trim()
+                    .parse::<SdpAttribute>()
+                    .map_err(|e| to_line_error(e, line, line_number)),
+            )
+        })
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+pub enum SdpLevel {
+    Session,
+    Media,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+pub struct IceCredentials {
+    pub ufrag: String,
+    pub pwd: String,
+    pub level: SdpLevel,
+}
+
+/// Returns the ICE credentials which apply to a media section. Credentials
+/// from the media section take precedence over the session level ones.
+pub fn ice_credentials(
+    session_attrs: &[SdpAttribute],
+    media_attrs: &[SdpAttribute],
+) -> Option<IceCredentials> {
+    let find_credentials = |attrs: &[SdpAttribute], level| {
+        let ufrag = attrs.iter().find_map(|a| match *a {
+            SdpAttribute::IceUfrag(ref ufrag) => Some(ufrag.clone()),
+            _ => None,
+        })?;
+        let pwd = attrs.iter().find_map(|a| match *a {
+            SdpAttribute::IcePwd(ref pwd) => Some(pwd.clone()),
+            _ => None,
+        })?;
+        Some(IceCredentials { ufrag, pwd, level })
+    };
+    find_credentials(media_attrs, SdpLevel::Media)
+        .or_else(|| find_credentials(session_attrs, SdpLevel::Session))
+}
+
+/// Parses a trickled ICE candidate, given either as "a=candidate:..." or as
+/// "candidate:...", and appends it to the existing attributes. Lines which
+/// are not candidate attributes get rejected.
+pub fn add_candidate(existing: &mut Vec<SdpAttribute>, line: &str) -> Result<(), SdpParserError> {
+    let trimmed = line.trim();
+    let value = trimmed.strip_prefix("a=").unwrap_or(trimmed);
+    match parse_attribute_with_config(value, &SdpParserConfig::default()) {
+        Ok(SdpType::Attribute(candidate @ SdpAttribute::Candidate(..))) => {
+            existing.push(candidate);
+            Ok(())
+        }
+        Ok(_) => Err(to_line_error(
+            SdpParserInternalError::Invalid(
+                SdpErrorKind::InvalidValue,
+                "Only candidate attributes can be added".to_string(),
+            ),
+            line,
+            0,
+        )),
+        Err(error) => Err(to_line_error(error, line, 0)),
+    }
+}
+
+/// Parses an attribute value like parse_attribute, but additionally reports
+/// non fatal issues, like deprecated attributes or legacy syntax.
+pub fn parse_attribute_with_warnings(
+    value: &str,
+) -> Result<(SdpLine, Vec<SdpWarning>), SdpParserError> {
+    let sdp_type = parse_attribute_with_config(value, &SdpParserConfig::default())
+        .map_err(|e| to_line_error(e, value, 0))?;
+    let mut warnings = Vec::new();
+    if let SdpType::Attribute(ref attribute) = sdp_type {
+        let attribute_type = SdpAttributeType::from(attribute);
+        if attribute_type.is_deprecated() {
+            warnings.push(SdpWarning::new(
+                SdpWarningKind::Deprecated,
+                &format!("{} is deprecated by JSEP", attribute_type),
+            ))
+        }
+    }
+    match sdp_type {
+        SdpType::Attribute(SdpAttribute::Setup(SdpAttributeSetup::Holdconn)) => {
+            warnings.push(SdpWarning::new(
+                SdpWarningKind::Deprecated,
+                "setup:holdconn is rarely used and must not be used by WebRTC endpoints",
+            ))
+        }
+        SdpType::Attribute(SdpAttribute::Simulcast(..)) if value.contains('=') => {
+            warnings.push(SdpWarning::new(
+                SdpWarningKind::LegacySyntax,
+                "simulcast uses the legacy draft-03 rid= notation",
+            ))
+        }
+        SdpType::Attribute(SdpAttribute::Candidate(ref candidate)) => {
+            warnings.extend(candidate.unknown_extensions.iter().map(|(name, _)| {
+                SdpWarning::new(
+                    SdpWarningKind::UnknownExtension,
+                    &format!("unknown ICE candidate extension '{}' was kept as is", name),
+                )
+            }))
+        }
+        _ => (),
+    }
+    Ok((
+        SdpLine {
+            line_number: 0,
+            sdp_type,
+            text: value.to_string(),
+            original: None,
+        },
+        warnings,
+    ))
+}
+
+/// Parses attribute lines, given with or without the "a=" prefix, without
+/// discarding the unsupported ones. Their names and raw values get returned
+/// as second vector, so that callers can log or pass them through. Malformed
+/// lines of supported attributes are skipped, this includes values a known
+/// attribute does not support, like an md5 fingerprint.
+pub fn parse_attributes_lenient(lines: &[&str]) -> (Vec<SdpAttribute>, Vec<(String, String)>) {
+    let mut attributes = Vec::new();
+    let mut unknown = Vec::new();
+    for line in lines {
+        let trimmed = line.trim();
+        let value = trimmed.strip_prefix("a=").unwrap_or(trimmed);
+        match parse_attribute_with_config(value, &SdpParserConfig::default()) {
+            Ok(SdpType::Attribute(attribute)) => attributes.push(attribute),
+            Ok(_) => (),
+            Err(SdpParserInternalError::Unsupported(_)) if !is_known_attribute(value) => {
+                let (name, raw_value) = match value.find(':') {
+                    Some(index) => (&value[..index], &value[index + 1..]),
+                    None => (value, ""),
+                };
+                unknown.push((name.to_string(), raw_value.to_string()));
+            }
+            Err(error) => warn!("Skipping malformed attribute '{}': {}", value, error),
+        }
+    }
+    (attributes, unknown)
+}
+
+// Whether the name of the attribute line maps to an SdpAttributeType, so
+// that unsupported values of known attributes are not taken for unknown
+// attributes.
+fn is_known_attribute(value: &str) -> bool {
+    let name = value.split(':').next().unwrap_or(value);
+    name.parse::<SdpAttributeType>().is_ok()
+}
+
+/// Parses attribute lines like parse_attributes_lenient, but reports every
+/// issue found as a diagnostic with a severity. The line numbers of the
+/// diagnostics are the indices into the given lines.
+pub fn parse_attributes_with_diagnostics(
+    lines: &[&str],
+) -> (Vec<SdpAttribute>, Vec<SdpDiagnostic>) {
+    let mut attributes = Vec::new();
+    let mut diagnostics = Vec::new();
+    for (line_number, line) in lines.iter().enumerate() {
+        let trimmed = line.trim();
+        let value = trimmed.strip_prefix("a=").unwrap_or(trimmed);
+        match parse_attribute_with_warnings(value) {
+            Ok((sdp_line, warnings)) => {
+                diagnostics.extend(
+                    warnings
+                        .into_iter()
+                        .map(|w| SdpDiagnostic::from_warning(w, line, line_number)),
+                );
+                if let SdpType::Attribute(attribute) = sdp_line.sdp_type {
+                    attributes.push(attribute);
+                }
+            }
+            Err(error) => {
+                let mut diagnostic = SdpDiagnostic::from(error);
+                // Only unknown attributes are informational, an unsupported
+                // value of a known attribute still makes the line unusable.
+                if is_known_attribute(value) {
+                    diagnostic.severity = SdpSeverity::Error;
+                }
+                diagnostic.line = line.to_string();
+                diagnostic.line_number = line_number;
+                diagnostics.push(diagnostic);
+            }
+        }
+    }
+    (attributes, diagnostics)
+}
+
 pub fn parse_sdp(sdp: &str, fail_on_warning: bool) -> Result<SdpSession, SdpParserError> {
+    parse_sdp_with_config(sdp, fail_on_warning, &SdpParserConfig::default())
+}
+
+pub fn parse_sdp_with_config(
+    sdp: &str,
+    fail_on_warning: bool,
+    config: &SdpParserConfig,
+) -> Result<SdpSession, SdpParserError> {
     if sdp.is_empty() {
         return Err(SdpParserError::Line {
-            error: SdpParserInternalError::Generic("empty SDP".to_string()),
+            error: SdpParserInternalError::Invalid(
+                SdpErrorKind::InvalidLine,
+                "empty SDP".to_string(),
+            ),
             line: sdp.to_string(),
             line_number: 0,
         });
@@ -852,12 +1395,15 @@ pub fn parse_sdp(sdp: &str, fail_on_warning: bool) -> Result<SdpSession, SdpPars
     // see test_parse_sdp_minimal_sdp_successfully
     if sdp.len() < 51 {
         return Err(SdpParserError::Line {
-            error: SdpParserInternalError::Generic("string too short to be valid SDP".to_string()),
+            error: SdpParserInternalError::Invalid(
+                SdpErrorKind::InvalidLine,
+                "string too short to be valid SDP".to_string(),
+            ),
             line: sdp.to_string(),
             line_number: 0,
         });
     }
-    let lines = sdp.lines();
+    let lines = split_lines(sdp);
     let mut errors: Vec<SdpParserError> = Vec::new();
     let mut warnings: Vec<SdpParserError> = Vec::new();
     let mut sdp_lines: Vec<SdpLine> = Vec::new();
@@ -866,7 +1412,7 @@ pub fn parse_sdp(sdp: &str, fail_on_warning: bool) -> Result<SdpSession, SdpPars
         if stripped_line.is_empty() {
             continue;
         }
-        match parse_sdp_line(line, line_number) {
+        match parse_sdp_line_with_config(line, line_number, config) {
             Ok(n) => {
                 sdp_lines.push(n);
             }
@@ -6,8 +6,11 @@ extern crate url;
 use super::*;
 use address::{Address, AddressType};
 use anonymizer::ToBytesVec;
+use attribute_type::parse_attribute;
+use error::{SdpDiagnosticKind, SdpSeverity};
 use std::net::IpAddr;
 use std::net::Ipv4Addr;
+use std::str::FromStr;
 
 fn create_dummy_sdp_session() -> SdpSession {
     let origin = parse_origin("mozilla 506705521068071134 0 IN IP4 0.0.0.0");
@@ -258,6 +261,31 @@ fn test_parse_sdp_line_invalid_a_line() {
     assert!(parse_sdp_line("a=rtpmap:200 PCMA/8000", 0).is_err());
 }
 
+#[test]
+fn test_parse_sdp_line_error_kind() {
+    let kind_of = |line: &str| parse_sdp_line(line, 0).err().unwrap().kind();
+    assert_eq!(kind_of("abcd"), SdpErrorKind::InvalidLine);
+    assert_eq!(kind_of("ab=foobar"), SdpErrorKind::InvalidLine);
+    assert_eq!(kind_of("t=0"), SdpErrorKind::TokenCount);
+    assert_eq!(kind_of("c=IN IP4"), SdpErrorKind::TokenCount);
+    assert_eq!(kind_of("v=1"), SdpErrorKind::InvalidValue);
+    assert_eq!(
+        kind_of("a=rtpmap:200 PCMA/8000"),
+        SdpErrorKind::InvalidNumber
+    );
+    assert_eq!(kind_of("a=sendrecv:foo"), SdpErrorKind::UnexpectedToken);
+    for line in &[
+        "e=foo", "i=foo", "k=foo", "p=foo", "r=foo", "u=foo", "z=foo",
+    ] {
+        match parse_sdp_line(line, 0) {
+            Err(error @ SdpParserError::Line { .. }) => {
+                assert_eq!(error.kind(), SdpErrorKind::UnsupportedLineType)
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+
 #[test]
 fn test_add_attribute() -> Result<(), SdpParserInternalError> {
     let mut sdp_session = create_dummy_sdp_session();
@@ -415,6 +443,56 @@ t=0 0\r\n",
     Ok(())
 }
 
+#[test]
+fn test_parse_sdp_datachannel_candidates() -> Result<(), SdpParserError> {
+    let sdp = parse_sdp(
+        "v=0\r\n\
+         o=- 0 0 IN IP4 0.0.0.0\r\n\
+         s=-\r\n\
+         t=0 0\r\n\
+         m=application 9 UDP/DTLS/SCTP webrtc-datachannel\r\n\
+         c=IN IP4 0.0.0.0\r\n\
+         a=sctp-port:5000\r\n\
+         a=candidate:0 1 UDP 2122252543 172.16.156.106 49760 typ host\r\n\
+         a=candidate:1 1 TCP 2105524479 172.16.156.106 9 typ host tcptype active\r\n",
+        true,
+    )?;
+    let candidates: Vec<&SdpAttribute> =
+        sdp.media[0].get_attributes_of_type(SdpAttributeType::Candidate);
+    assert_eq!(candidates.len(), 2);
+    for candidate in candidates {
+        match *candidate {
+            SdpAttribute::Candidate(ref c) => assert!(c.can_carry_data_channel()),
+            _ => unreachable!(),
+        }
+    }
+
+    let rtcp = SdpAttribute::from_str("candidate:0 2 UDP 2122252542 172.16.156.106 49761 typ host")
+        .unwrap();
+    match rtcp {
+        SdpAttribute::Candidate(ref c) => assert!(!c.can_carry_data_channel()),
+        _ => unreachable!(),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_parse_sdp_candidate_extension_without_value() {
+    let sdp = "v=0\r\n\
+               o=- 0 0 IN IP4 0.0.0.0\r\n\
+               s=-\r\n\
+               t=0 0\r\n\
+               m=audio 9 UDP/TLS/RTP/SAVPF 109\r\n\
+               c=IN IP4 0.0.0.0\r\n\
+               a=candidate:1 1 TCP 2105524479 172.16.156.106 9 typ host tcptype\r\n";
+    match parse_sdp(sdp, false) {
+        Err(error @ SdpParserError::Line { .. }) => {
+            assert_eq!(error.kind(), SdpErrorKind::MissingToken)
+        }
+        _ => unreachable!(),
+    }
+}
+
 #[test]
 fn test_parse_sdp_too_short() {
     assert!(parse_sdp(
@@ -756,3 +834,446 @@ fn test_session_add_media_invalid_attribute_fails() -> Result<(), SdpParserInter
         .is_err());
     Ok(())
 }
+
+#[test]
+fn test_split_attribute_blocks() {
+    let sdp = "v=0\r\n\
+               o=- 0 0 IN IP4 0.0.0.0\r\n\
+               s=-\r\n\
+               t=0 0\r\n\
+               a=ice-lite\r\n\
+               a=group:BUNDLE audio video\r\n\
+               m=audio 9 UDP/TLS/RTP/SAVPF 109\r\n\
+               c=IN IP4 0.0.0.0\r\n\
+               a=mid:audio\r\n\
+               a=rtpmap:109 opus/48000/2\r\n\
+               m=video 9 UDP/TLS/RTP/SAVPF 120\r\n\
+               a=mid:video\r\n";
+    let (session, media) = split_attribute_blocks(sdp);
+    assert_eq!(session, vec!["ice-lite", "group:BUNDLE audio video"]);
+    assert_eq!(
+        media,
+        vec![
+            vec!["mid:audio", "rtpmap:109 opus/48000/2"],
+            vec!["mid:video"]
+        ]
+    );
+    for attr in session.iter().chain(media.iter().flatten()) {
+        assert!(parse_attribute(attr).is_ok());
+    }
+
+    let (session, media) = split_attribute_blocks("v=0\r\n");
+    assert!(session.is_empty());
+    assert!(media.is_empty());
+}
+
+#[test]
+fn test_iter_attributes() {
+    let sdp = "v=0\r\n\
+               o=- 0 0 IN IP4 0.0.0.0\r\n\
+               s=-\r\n\
+               t=0 0\r\n\
+               a=ice-lite\r\n\
+               m=audio 9 UDP/TLS/RTP/SAVPF 109\r\n\
+               c=IN IP4 0.0.0.0\r\n\
+               a=mid:audio\r\n\
+               a=rtpmap:109 opus/48000/2\r\n\
+               a=ptime:foo\r\n";
+    let (session, media) = split_attribute_blocks(sdp);
+    let lines: Vec<&str> = session
+        .into_iter()
+        .chain(media.into_iter().flatten())
+        .collect();
+    let (eager, _) = parse_attributes_lenient(&lines);
+
+    let lazy: Vec<SdpAttribute> = iter_attributes(sdp).filter_map(Result::ok).collect();
+    assert!(lazy == eager);
+
+    let errors: Vec<SdpParserError> = iter_attributes(sdp).filter_map(Result::err).collect();
+    assert_eq!(errors.len(), 1);
+    match errors[0] {
+        SdpParserError::Line { line_number, .. } => assert_eq!(line_number, 9),
+        _ => unreachable!(),
+    }
+
+    let mut iter = iter_attributes(sdp);
+    assert!(iter.next().unwrap().unwrap() == SdpAttribute::IceLite);
+}
+
+#[test]
+fn test_split_lines_line_endings() {
+    let crlf = "a=ice-lite\r\nm=audio 9 UDP/TLS/RTP/SAVPF 109\r\na=mid:audio\r\n";
+    let lf = "a=ice-lite\nm=audio 9 UDP/TLS/RTP/SAVPF 109\na=mid:audio\n";
+    let mixed = "a=ice-lite\r\nm=audio 9 UDP/TLS/RTP/SAVPF 109\na=mid:audio\r\r\n";
+    let expected: Vec<&str> = split_lines(crlf).collect();
+    assert_eq!(
+        expected,
+        [
+            "a=ice-lite",
+            "m=audio 9 UDP/TLS/RTP/SAVPF 109",
+            "a=mid:audio"
+        ]
+    );
+    assert_eq!(split_lines(lf).collect::<Vec<_>>(), expected);
+    assert_eq!(split_lines(mixed).collect::<Vec<_>>(), expected);
+
+    let blocks = split_attribute_blocks(crlf);
+    assert_eq!(blocks, (vec!["ice-lite"], vec![vec!["mid:audio"]]));
+    assert_eq!(split_attribute_blocks(lf), blocks);
+    assert_eq!(split_attribute_blocks(mixed), blocks);
+}
+
+#[test]
+fn test_check_cardinality() -> Result<(), SdpParserInternalError> {
+    let attrs = vec![
+        SdpAttribute::from_str("mid:audio")?,
+        SdpAttribute::from_str("candidate:0 1 UDP 2122252543 172.16.156.106 49760 typ host")?,
+        SdpAttribute::from_str("candidate:1 1 UDP 2122252542 172.16.156.107 49761 typ host")?,
+        SdpAttribute::from_str("sendrecv")?,
+    ];
+    assert!(check_cardinality(&attrs).is_empty());
+
+    let mut duplicated = attrs.clone();
+    duplicated.push(SdpAttribute::from_str("mid:video")?);
+    duplicated.push(SdpAttribute::from_str("mid:data")?);
+    let errors = check_cardinality(&duplicated);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].kind(), SdpErrorKind::Sequence);
+    match errors[0] {
+        SdpParserError::Line {
+            ref error,
+            ref line,
+            line_number,
+        } => {
+            assert_eq!(
+                error.to_string(),
+                "Parsing error: Attribute mid must not appear more than once"
+            );
+            assert_eq!(line, "mid:video");
+            assert_eq!(line_number, 4);
+        }
+        _ => unreachable!(),
+    }
+
+    let mut directions = attrs.clone();
+    directions.push(SdpAttribute::from_str("recvonly")?);
+    let errors = check_cardinality(&directions);
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0]
+        .to_string()
+        .contains("Only one direction attribute is allowed"));
+    Ok(())
+}
+
+#[test]
+fn test_add_candidate() -> Result<(), SdpParserError> {
+    let mut attrs = vec![SdpAttribute::RtcpMux];
+    add_candidate(
+        &mut attrs,
+        "a=candidate:0 1 UDP 2122252543 172.16.156.106 49760 typ host",
+    )?;
+    add_candidate(
+        &mut attrs,
+        "candidate:1 1 UDP 1685987071 24.23.204.141 54609 typ srflx raddr 192.168.1.4 rport 61665",
+    )?;
+    assert_eq!(attrs.len(), 3);
+    assert!(matches!(attrs[1], SdpAttribute::Candidate(..)));
+    assert!(matches!(attrs[2], SdpAttribute::Candidate(..)));
+
+    assert!(add_candidate(&mut attrs, "a=mid:sdparta_0").is_err());
+    assert!(add_candidate(&mut attrs, "a=candidate:0 1 UDP 2122252543").is_err());
+    assert!(add_candidate(&mut attrs, "a=foobar").is_err());
+    assert_eq!(attrs.len(), 3);
+    Ok(())
+}
+
+#[test]
+fn test_ice_credentials() {
+    let session = vec![
+        SdpAttribute::IceUfrag("session_ufrag".to_string()),
+        SdpAttribute::IcePwd("session_pwd".to_string()),
+    ];
+    let media = vec![
+        SdpAttribute::IceUfrag("media_ufrag".to_string()),
+        SdpAttribute::IcePwd("media_pwd".to_string()),
+    ];
+
+    assert_eq!(
+        ice_credentials(&session, &media),
+        Some(IceCredentials {
+            ufrag: "media_ufrag".to_string(),
+            pwd: "media_pwd".to_string(),
+            level: SdpLevel::Media,
+        })
+    );
+    assert_eq!(
+        ice_credentials(&session, &[SdpAttribute::RtcpMux]),
+        Some(IceCredentials {
+            ufrag: "session_ufrag".to_string(),
+            pwd: "session_pwd".to_string(),
+            level: SdpLevel::Session,
+        })
+    );
+    // Incomplete media level credentials do not override the session ones
+    assert_eq!(
+        ice_credentials(&session, &media[..1]).map(|c| c.level),
+        Some(SdpLevel::Session)
+    );
+    assert_eq!(ice_credentials(&[], &media[1..]), None);
+}
+
+#[test]
+fn test_parse_attribute_with_warnings() -> Result<(), SdpParserError> {
+    let (line, warnings) = parse_attribute_with_warnings("msid-semantic:WMS *")?;
+    assert!(matches!(
+        line.sdp_type,
+        SdpType::Attribute(SdpAttribute::MsidSemantic(..))
+    ));
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].kind, SdpWarningKind::Deprecated);
+
+    let (line, warnings) = parse_attribute_with_warnings("ssrc-group:FID 3156517279 2673335628")?;
+    assert!(matches!(
+        line.sdp_type,
+        SdpType::Attribute(SdpAttribute::SsrcGroup(..))
+    ));
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].kind, SdpWarningKind::Deprecated);
+    assert_eq!(warnings[0].message, "ssrc-group is deprecated by JSEP");
+
+    let (_, warnings) = parse_attribute_with_warnings("sctpmap:5000 webrtc-datachannel 256")?;
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].kind, SdpWarningKind::Deprecated);
+    let (_, warnings) = parse_attribute_with_warnings("sctp-port:5000")?;
+    assert!(warnings.is_empty());
+
+    let (line, warnings) = parse_attribute_with_warnings("simulcast: send rid=foo;bar")?;
+    assert!(matches!(
+        line.sdp_type,
+        SdpType::Attribute(SdpAttribute::Simulcast(..))
+    ));
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].kind, SdpWarningKind::LegacySyntax);
+
+    let (line, warnings) = parse_attribute_with_warnings("simulcast:send foo;bar")?;
+    assert_eq!(line.text, "simulcast:send foo;bar");
+    assert!(warnings.is_empty());
+
+    let (line, warnings) = parse_attribute_with_warnings("setup:holdconn")?;
+    assert!(matches!(
+        line.sdp_type,
+        SdpType::Attribute(SdpAttribute::Setup(SdpAttributeSetup::Holdconn))
+    ));
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].kind, SdpWarningKind::Deprecated);
+    assert!(warnings[0].message.contains("holdconn"));
+    let (_, warnings) = parse_attribute_with_warnings("setup:actpass")?;
+    assert!(warnings.is_empty());
+
+    let (_, warnings) = parse_attribute_with_warnings(
+        "candidate:0 1 UDP 2122252543 172.16.156.106 49760 typ host x-foo bar",
+    )?;
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].kind, SdpWarningKind::UnknownExtension);
+    assert!(warnings[0].message.contains("x-foo"));
+    let (_, warnings) = parse_attribute_with_warnings(
+        "candidate:0 1 UDP 2122252543 172.16.156.106 49760 typ host generation 0",
+    )?;
+    assert!(warnings.is_empty());
+
+    assert!(parse_attribute_with_warnings("mid").is_err());
+    Ok(())
+}
+
+#[test]
+fn test_parse_attributes_lenient() {
+    let (attributes, unknown) = parse_attributes_lenient(&[
+        "a=mid:sdparta_0",
+        "x-google-flag:conference",
+        "a=rtcp-mux",
+        "a=x-vendor-thing",
+        "ptime:foo",
+        "fingerprint:md5 CD:34",
+        "ts-refclk:foo",
+        "candidate:0 1 UDP 2122252543 172.16.156.106 49760 typ host generation",
+    ]);
+    let names: Vec<String> = attributes
+        .iter()
+        .map(|a| SdpAttributeType::from(a).to_string())
+        .collect();
+    assert_eq!(names, ["mid", "rtcp-mux"]);
+    assert_eq!(
+        unknown,
+        vec![
+            ("x-google-flag".to_string(), "conference".to_string()),
+            ("x-vendor-thing".to_string(), "".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_parse_attributes_with_diagnostics() {
+    let (attributes, diagnostics) = parse_attributes_with_diagnostics(&[
+        "a=mid:sdparta_0",
+        "a=msid-semantic:WMS *",
+        "a=x-vendor-thing",
+        "a=ptime:foo",
+        "a=fingerprint:md5 CD:34",
+    ]);
+    assert_eq!(attributes.len(), 2);
+
+    let severities: Vec<(SdpSeverity, usize)> = diagnostics
+        .iter()
+        .map(|d| (d.severity, d.line_number))
+        .collect();
+    assert_eq!(
+        severities,
+        [
+            (SdpSeverity::Warning, 1),
+            (SdpSeverity::Info, 2),
+            (SdpSeverity::Error, 3),
+            (SdpSeverity::Error, 4)
+        ]
+    );
+    assert_eq!(
+        diagnostics[0].kind,
+        SdpDiagnosticKind::Warning(SdpWarningKind::Deprecated)
+    );
+    assert_eq!(
+        diagnostics[1].kind,
+        SdpDiagnosticKind::Error(SdpErrorKind::Unsupported)
+    );
+    assert_eq!(
+        diagnostics[2].kind,
+        SdpDiagnosticKind::Error(SdpErrorKind::InvalidNumber)
+    );
+    assert_eq!(diagnostics[2].line, "a=ptime:foo");
+    assert_eq!(
+        diagnostics[3].kind,
+        SdpDiagnosticKind::Error(SdpErrorKind::Unsupported)
+    );
+    assert!(diagnostics[2].to_string().starts_with("Error: "));
+}
+
+#[test]
+fn test_check_extmap_ids() -> Result<(), SdpParserInternalError> {
+    let mixed = [
+        SdpAttribute::ExtmapAllowMixed,
+        SdpAttribute::from_str("extmap:14 urn:example:foo")?,
+        SdpAttribute::from_str("extmap:15 urn:example:bar")?,
+    ];
+    let (allow_mixed, extmaps) = mixed.split_at(1);
+
+    assert!(check_extmap_ids(&[], &extmaps[..1]).is_empty());
+    assert!(check_extmap_ids(allow_mixed, extmaps).is_empty());
+    assert!(check_extmap_ids(&[], &mixed).is_empty());
+
+    let errors = check_extmap_ids(&[], extmaps);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].kind(), SdpErrorKind::Sequence);
+    assert!(errors[0].to_string().contains("Extmap id 15"));
+    Ok(())
+}
+
+#[test]
+fn test_diff_attributes() -> Result<(), SdpParserInternalError> {
+    let old = [
+        SdpAttribute::from_str("mid:audio")?,
+        SdpAttribute::from_str("sendrecv")?,
+        SdpAttribute::from_str("rtpmap:109 opus/48000/2")?,
+        SdpAttribute::from_str("candidate:0 1 UDP 2122252543 172.16.156.106 49760 typ host")?,
+    ];
+    let new = [
+        SdpAttribute::from_str("mid:audio")?,
+        SdpAttribute::from_str("rtpmap:109 opus/48000/2")?,
+        SdpAttribute::from_str("sendonly")?,
+        SdpAttribute::from_str("candidate:0 1 UDP 2122252543 172.16.156.106 49760 typ host")?,
+        SdpAttribute::from_str("candidate:1 1 UDP 1685987071 24.23.204.141 54609 typ srflx raddr 192.168.1.4 rport 61665")?,
+    ];
+
+    let diff = diff_attributes(&old, &new);
+    assert_eq!(diff.added.len(), 1);
+    assert!(diff.added[0] == new[4]);
+    assert!(diff.removed.is_empty());
+    assert_eq!(diff.changed.len(), 1);
+    assert!(diff.changed[0] == (SdpAttribute::Sendrecv, SdpAttribute::Sendonly));
+
+    let reverse = diff_attributes(&new, &old);
+    assert_eq!(reverse.removed.len(), 1);
+    assert!(reverse.removed[0] == new[4]);
+    assert!(reverse.added.is_empty());
+
+    assert!(diff_attributes(&old, &old).is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_parse_sdp_preserve_original() -> Result<(), SdpParserError> {
+    let sdp = "v=0\r\n\
+               o=- 0 0 IN IP4 0.0.0.0\r\n\
+               s=-\r\n\
+               t=0 0\r\n\
+               a=ICE-UFRAG:4a799b2e\r\n\
+               m=audio 9 UDP/TLS/RTP/SAVPF 109\r\n\
+               c=IN IP4 0.0.0.0\r\n\
+               a=rtpmap:109 OPUS/48000/2\r\n\
+               a=fmtp:109 stereo=1; useinbandfec=1 \r\n\
+               a=candidate:0 1 udp 2122252543 172.16.156.106 49760 typ host\r\n\
+               a=sendrecv\r\n";
+    let preserve = SdpParserConfig {
+        preserve_original: true,
+        ..Default::default()
+    };
+    let line = parse_sdp_line_with_config("a=fmtp:109 stereo=1; useinbandfec=1 ", 0, &preserve)?;
+    assert_eq!(
+        line.original,
+        Some("a=fmtp:109 stereo=1; useinbandfec=1 ".to_string())
+    );
+    assert!(parse_sdp_line("a=fmtp:109 stereo=1", 0)?.original.is_none());
+    assert!(parse_sdp_line_with_config("t=0 0", 0, &preserve)?
+        .original
+        .is_none());
+
+    let session = parse_sdp_with_config(sdp, true, &preserve)?;
+    assert_eq!(session.to_string(), sdp);
+    assert!(
+        session.get_attribute(SdpAttributeType::IceUfrag)
+            == Some(&SdpAttribute::IceUfrag("4a799b2e".to_string()))
+    );
+    assert_eq!(session.get_original(0), Some("a=ICE-UFRAG:4a799b2e"));
+    assert_eq!(session.get_original(1), None);
+    let media = &session.media[0];
+    assert_eq!(media.get_original(0), Some("a=rtpmap:109 OPUS/48000/2"));
+    assert_eq!(
+        media.get_original(1),
+        Some("a=fmtp:109 stereo=1; useinbandfec=1 ")
+    );
+
+    // Changed attributes are emitted in their canonical form, the others
+    // keep their original line even if attributes before them got removed
+    let mut changed = session.clone();
+    changed.media[0].remove_attribute(SdpAttributeType::Rtpmap);
+    assert!(changed.media[0]
+        .set_attribute(SdpAttribute::Recvonly)
+        .is_ok());
+    changed.attribute[0] = SdpAttribute::IceUfrag("4a799b2e".to_string());
+    changed.attribute.insert(0, SdpAttribute::IceLite);
+    let serialized = changed.to_string();
+    assert!(serialized.contains("a=ice-lite\r\na=ice-ufrag:4a799b2e\r\n"));
+    assert!(serialized.contains("a=fmtp:109 stereo=1; useinbandfec=1 \r\n"));
+    assert!(serialized.contains("a=recvonly\r\n"));
+    assert!(!serialized.contains("a=rtpmap"));
+    assert_eq!(
+        changed.media[0].get_original(0),
+        Some("a=fmtp:109 stereo=1; useinbandfec=1 ")
+    );
+    assert!(changed.get_original(1).is_none());
+
+    // Anonymizing must not keep the original text of the masked values
+    let masked = session.masked_clone(&mut StatefulSdpAnonymizer::new());
+    assert!(!masked.to_string().contains("4a799b2e"));
+    assert!(masked.get_original(0).is_none());
+
+    assert_ne!(parse_sdp(sdp, true)?.to_string(), sdp);
+    Ok(())
+}
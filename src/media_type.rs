@@ -6,9 +6,10 @@ use anonymizer::{AnonymizingClone, StatefulSdpAnonymizer};
 use attribute_type::{
     maybe_print_param, SdpAttribute, SdpAttributeRtpmap, SdpAttributeSctpmap, SdpAttributeType,
 };
-use error::{SdpParserError, SdpParserInternalError};
+use error::{SdpErrorKind, SdpParserError, SdpParserInternalError};
 use std::fmt;
-use {SdpBandwidth, SdpConnection, SdpLine, SdpType};
+use {attributes_to_sdp_preserving, original_at, record_original};
+use {SdpBandwidth, SdpConnection, SdpLine, SdpOriginals, SdpType};
 
 /*
  * RFC4566
@@ -94,6 +95,37 @@ impl fmt::Display for SdpProtocolValue {
     }
 }
 
+impl SdpProtocolValue {
+    pub fn transport(&self) -> SdpTransportProtocol {
+        match *self {
+            SdpProtocolValue::RtpAvp | SdpProtocolValue::RtpAvpf => SdpTransportProtocol::Rtp,
+            SdpProtocolValue::RtpSavp
+            | SdpProtocolValue::RtpSavpf
+            | SdpProtocolValue::TcpDtlsRtpSavp
+            | SdpProtocolValue::TcpDtlsRtpSavpf
+            | SdpProtocolValue::UdpTlsRtpSavp
+            | SdpProtocolValue::UdpTlsRtpSavpf => SdpTransportProtocol::SecureRtp,
+            SdpProtocolValue::DtlsSctp
+            | SdpProtocolValue::UdpDtlsSctp
+            | SdpProtocolValue::TcpDtlsSctp => SdpTransportProtocol::Sctp,
+        }
+    }
+}
+
+/// Coarse classification of the media protocols, which determines the set of
+/// attributes that make sense for a media section.
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serialize", derive(Serialize))]
+pub enum SdpTransportProtocol {
+    Rtp,
+    SecureRtp,
+    Sctp,
+}
+
+pub fn classify_transport(proto: &str) -> Result<SdpTransportProtocol, SdpParserInternalError> {
+    Ok(parse_protocol_token(proto)?.transport())
+}
+
 #[derive(Clone)]
 #[cfg_attr(feature = "serialize", derive(Serialize))]
 #[cfg_attr(feature = "enhanced_debug", derive(Debug))]
@@ -129,6 +161,11 @@ pub struct SdpMedia {
     connection: Option<SdpConnection>,
     bandwidth: Vec<SdpBandwidth>,
     attribute: Vec<SdpAttribute>,
+    // The original line of the attribute at the same index, together with
+    // the attribute as it was parsed. Only filled with
+    // SdpParserConfig::preserve_original
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    originals: SdpOriginals,
     // unsupported values:
     // information: Option<String>,
     // key: Option<String>,
@@ -142,7 +179,7 @@ impl fmt::Display for SdpMedia {
             mline = self.media,
             bw = maybe_vector_to_string!("b={}\r\n", self.bandwidth, "\r\nb="),
             connection = option_to_string!("c={}\r\n", self.connection),
-            attributes = maybe_vector_to_string!("a={}\r\n", self.attribute, "\r\na=")
+            attributes = attributes_to_sdp_preserving(&self.attribute, &self.originals)
         )
     }
 }
@@ -154,6 +191,7 @@ impl SdpMedia {
             connection: None,
             bandwidth: Vec::new(),
             attribute: Vec::new(),
+            originals: Vec::new(),
         }
     }
 
@@ -195,10 +233,10 @@ impl SdpMedia {
 
     pub fn add_attribute(&mut self, attr: SdpAttribute) -> Result<(), SdpParserInternalError> {
         if !attr.allowed_at_media_level() {
-            return Err(SdpParserInternalError::Generic(format!(
-                "{} not allowed at media level",
-                attr
-            )));
+            return Err(SdpParserInternalError::Invalid(
+                SdpErrorKind::Sequence,
+                format!("{} not allowed at media level", attr),
+            ));
         }
         self.attribute.push(attr);
         Ok(())
@@ -210,8 +248,33 @@ impl SdpMedia {
             .find(|a| SdpAttributeType::from(*a) == t)
     }
 
+    /// The original line of the attribute at the given index, if it got
+    /// parsed with SdpParserConfig::preserve_original and has not been
+    /// changed or moved since.
+    pub fn get_original(&self, index: usize) -> Option<&str> {
+        original_at(&self.attribute, &self.originals, index)
+    }
+
+    // Like Vec::retain, but keeps the original lines next to their attributes
+    fn retain_attributes<F: Fn(&SdpAttribute) -> bool>(&mut self, keep: F) {
+        if self.originals.is_empty() {
+            self.attribute.retain(keep);
+            return;
+        }
+        let mut originals = std::mem::take(&mut self.originals);
+        originals.resize(self.attribute.len(), None);
+        let (attribute, originals) = self
+            .attribute
+            .drain(..)
+            .zip(originals)
+            .filter(|(attr, _)| keep(attr))
+            .unzip();
+        self.attribute = attribute;
+        self.originals = originals;
+    }
+
     pub fn remove_attribute(&mut self, t: SdpAttributeType) {
-        self.attribute.retain(|a| SdpAttributeType::from(a) != t);
+        self.retain_attributes(|a| SdpAttributeType::from(a) != t);
     }
 
     pub fn set_attribute(&mut self, attr: SdpAttribute) -> Result<(), SdpParserInternalError> {
@@ -225,7 +288,7 @@ impl SdpMedia {
             SdpFormatList::Strings(_) => self.media.formats = SdpFormatList::Strings(Vec::new()),
         }
 
-        self.attribute.retain({
+        self.retain_attributes({
             |x| {
                 !matches!(
                     *x,
@@ -303,6 +366,8 @@ impl AnonymizingClone for SdpMedia {
             bandwidth: self.bandwidth.clone(),
             connection: self.connection.clone(),
             attribute: Vec::new(),
+            // The original text would leak the unmasked values
+            originals: Vec::new(),
         };
         for i in &self.attribute {
             masked.attribute.push(i.masked_clone(anon));
@@ -350,7 +415,8 @@ fn parse_protocol_token(value: &str) -> Result<SdpProtocolValue, SdpParserIntern
 pub fn parse_media(value: &str) -> Result<SdpType, SdpParserInternalError> {
     let mv: Vec<&str> = value.split_whitespace().collect();
     if mv.len() < 4 {
-        return Err(SdpParserInternalError::Generic(
+        return Err(SdpParserInternalError::Invalid(
+            SdpErrorKind::MissingToken,
             "media attribute must have at least four tokens".to_string(),
         ));
     }
@@ -358,14 +424,16 @@ pub fn parse_media(value: &str) -> Result<SdpType, SdpParserInternalError> {
     let mut ptokens = mv[1].split('/');
     let port = match ptokens.next() {
         None => {
-            return Err(SdpParserInternalError::Generic(
+            return Err(SdpParserInternalError::Invalid(
+                SdpErrorKind::MissingToken,
                 "missing port token".to_string(),
             ));
         }
         Some(p) => p.parse::<u32>()?,
     };
     if port > 65535 {
-        return Err(SdpParserInternalError::Generic(
+        return Err(SdpParserInternalError::Invalid(
+            SdpErrorKind::PortOutOfRange,
             "media port token is too big".to_string(),
         ));
     }
@@ -386,7 +454,8 @@ pub fn parse_media(value: &str) -> Result<SdpType, SdpParserInternalError> {
                     9  |  // G722
                     13 |  // Comfort Noise
                     35 ..= 63 | 96 ..= 127 => (),  // dynamic range
-                    _ => return Err(SdpParserInternalError::Generic(
+                    _ => return Err(SdpParserInternalError::Invalid(
+                          SdpErrorKind::InvalidNumber,
                           "format number in media line is out of range".to_string()))
                 };
                 fmt_vec.push(fmt_num);
@@ -443,6 +512,7 @@ pub fn parse_media_vector(lines: &mut Vec<SdpLine>) -> Result<Vec<SdpMedia>, Sdp
             }
             SdpType::Bandwidth(b) => sdp_media.add_bandwidth(b),
             SdpType::Attribute(a) => {
+                let count = sdp_media.attribute.len();
                 match a {
                     SdpAttribute::DtlsMessage(_) => {
                         // Ignore this attribute on media level
@@ -461,7 +531,13 @@ pub fn parse_media_vector(lines: &mut Vec<SdpLine>) -> Result<Vec<SdpMedia>, Sdp
                 .map_err(|e: SdpParserInternalError| SdpParserError::Sequence {
                     message: format!("{}", e),
                     line_number: _line_number,
-                })?
+                })?;
+                // Ignored attributes have no place for their original line
+                if let Some(original) = line.original {
+                    if sdp_media.attribute.len() > count {
+                        record_original(&sdp_media.attribute, &mut sdp_media.originals, original);
+                    }
+                }
             }
             SdpType::Media(v) => {
                 media_sections.push(sdp_media);
@@ -42,7 +42,7 @@ pub fn add_dummy_attributes(media: &mut SdpMedia) {
         .is_ok());
     assert!(media
         .add_attribute(SdpAttribute::Fmtp(SdpAttributeFmtp {
-            payload_type: 1,
+            payload_type: SdpAttributePayloadType::PayloadType(1),
             parameters: SdpAttributeFmtpParameters {
                 packetization_mode: 0,
                 level_asymmetry_allowed: false,
@@ -57,6 +57,7 @@ pub fn add_dummy_attributes(media: &mut SdpMedia) {
                 useinbandfec: false,
                 cbr: false,
                 max_fr: 0,
+                profile_id: None,
                 maxplaybackrate: 48000,
                 maxaveragebitrate: 0,
                 ptime: 0,
@@ -66,7 +67,8 @@ pub fn add_dummy_attributes(media: &mut SdpMedia) {
                 dtmf_tones: "".to_string(),
                 rtx: None,
                 unknown_tokens: Vec::new()
-            }
+            },
+            tokens: Vec::new()
         },))
         .is_ok());
     assert!(media
@@ -267,6 +269,33 @@ fn test_parse_protocol_sctp_token() -> Result<(), SdpParserInternalError> {
     Ok(())
 }
 
+#[test]
+fn test_classify_transport() -> Result<(), SdpParserInternalError> {
+    assert_eq!(
+        classify_transport("UDP/TLS/RTP/SAVPF")?,
+        SdpTransportProtocol::SecureRtp
+    );
+    assert_eq!(
+        classify_transport("TCP/DTLS/RTP/SAVPF")?,
+        SdpTransportProtocol::SecureRtp
+    );
+    assert_eq!(
+        classify_transport("RTP/SAVP")?,
+        SdpTransportProtocol::SecureRtp
+    );
+    assert_eq!(classify_transport("RTP/AVP")?, SdpTransportProtocol::Rtp);
+    assert_eq!(classify_transport("rtp/avpf")?, SdpTransportProtocol::Rtp);
+    assert_eq!(classify_transport("DTLS/SCTP")?, SdpTransportProtocol::Sctp);
+    assert_eq!(
+        classify_transport("UDP/DTLS/SCTP")?,
+        SdpTransportProtocol::Sctp
+    );
+
+    assert!(classify_transport("").is_err());
+    assert!(classify_transport("RTP/FOO").is_err());
+    Ok(())
+}
+
 #[test]
 fn test_media_works() {
     check_parse_and_serialize("audio 9 UDP/TLS/RTP/SAVPF 109");
@@ -316,6 +345,7 @@ fn test_media_vector_first_line_failure() {
         line_number: 0,
         sdp_type: SdpType::Session("hello".to_string()),
         text: "".to_owned(),
+        original: None,
     };
     sdp_lines.push(line);
     assert!(parse_media_vector(&mut sdp_lines).is_err());
@@ -335,6 +365,7 @@ fn test_media_vector_multiple_connections() {
         line_number: 0,
         sdp_type: SdpType::Media(media_line),
         text: "".to_owned(),
+        original: None,
     };
     sdp_lines.push(media);
     let c = SdpConnection {
@@ -346,12 +377,14 @@ fn test_media_vector_multiple_connections() {
         line_number: 1,
         sdp_type: SdpType::Connection(c.clone()),
         text: "".to_owned(),
+        original: None,
     };
     sdp_lines.push(c1);
     let c2 = SdpLine {
         line_number: 2,
         sdp_type: SdpType::Connection(c),
         text: "".to_owned(),
+        original: None,
     };
     sdp_lines.push(c2);
     assert!(parse_media_vector(&mut sdp_lines).is_err());
@@ -371,6 +404,7 @@ fn test_media_vector_invalid_types() {
         line_number: 0,
         sdp_type: SdpType::Media(media_line),
         text: "".to_owned(),
+        original: None,
     };
     sdp_lines.push(media);
     use SdpTiming;
@@ -379,6 +413,7 @@ fn test_media_vector_invalid_types() {
         line_number: 1,
         sdp_type: SdpType::Timing(t),
         text: "".to_owned(),
+        original: None,
     };
     sdp_lines.push(tline);
     assert!(parse_media_vector(&mut sdp_lines).is_err());
@@ -398,6 +433,7 @@ fn test_media_vector_invalid_media_level_attribute() {
         line_number: 0,
         sdp_type: SdpType::Media(media_line),
         text: "".to_owned(),
+        original: None,
     };
     sdp_lines.push(media);
     let a = SdpAttribute::IceLite;
@@ -405,6 +441,7 @@ fn test_media_vector_invalid_media_level_attribute() {
         line_number: 1,
         sdp_type: SdpType::Attribute(a),
         text: "".to_owned(),
+        original: None,
     };
     sdp_lines.push(aline);
     assert!(parse_media_vector(&mut sdp_lines).is_err());
@@ -3,7 +3,7 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
 use address::{Address, AddressType};
-use error::SdpParserInternalError;
+use error::{SdpErrorKind, SdpParserInternalError};
 use std::net::IpAddr;
 use std::str::FromStr;
 
@@ -16,7 +16,8 @@ pub fn ip_address_to_string(addr: IpAddr) -> String {
 
 pub fn parse_network_type(value: &str) -> Result<(), SdpParserInternalError> {
     if value.to_uppercase() != "IN" {
-        return Err(SdpParserInternalError::Generic(
+        return Err(SdpParserInternalError::Invalid(
+            SdpErrorKind::InvalidValue,
             "nettype must be IN".to_string(),
         ));
     };
@@ -24,8 +25,12 @@ pub fn parse_network_type(value: &str) -> Result<(), SdpParserInternalError> {
 }
 
 pub fn parse_address_type(value: &str) -> Result<AddressType, SdpParserInternalError> {
-    AddressType::from_str(value.to_uppercase().as_str())
-        .map_err(|_| SdpParserInternalError::Generic("address type must be IP4 or IP6".to_string()))
+    AddressType::from_str(value.to_uppercase().as_str()).map_err(|_| {
+        SdpParserInternalError::Invalid(
+            SdpErrorKind::UnknownAddressType,
+            "address type must be IP4 or IP6".to_string(),
+        )
+    })
 }
 
 pub fn parse_unicast_address(value: &str) -> Result<Address, SdpParserInternalError> {
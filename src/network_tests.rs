@@ -31,3 +31,14 @@ fn test_parse_unicast_address() -> Result<(), SdpParserInternalError> {
     parse_unicast_address("::1")?;
     Ok(())
 }
+
+#[test]
+fn test_parse_unicast_address_ipv4_compatible() -> Result<(), SdpParserInternalError> {
+    for value in &["::127.0.0.1", "::ffff:127.0.0.1"] {
+        match parse_unicast_address(value)? {
+            Address::Ip(ip) => assert_eq!(AddressType::from_ip(&ip), AddressType::IpV6),
+            Address::Fqdn(_) => unreachable!(),
+        }
+    }
+    Ok(())
+}
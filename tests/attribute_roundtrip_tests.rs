@@ -0,0 +1,152 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+extern crate proptest;
+extern crate webrtc_sdp;
+
+use proptest::prelude::*;
+use std::net::IpAddr;
+use std::str::FromStr;
+use webrtc_sdp::address::{Address, ExplicitlyTypedAddress};
+use webrtc_sdp::attribute_type::{
+    SdpAttribute, SdpAttributeCandidate, SdpAttributeCandidateTransport, SdpAttributeCandidateType,
+    SdpAttributeFmtp, SdpAttributeFmtpParameters, SdpAttributePayloadType, SdpAttributeRtcp,
+    SdpAttributeRtpmap,
+};
+
+// Serializes the attribute, parses the result again and checks that both
+// the parsed attribute and its serialization match the original.
+fn check_roundtrip(attribute: SdpAttribute) -> Result<(), TestCaseError> {
+    let serialized = attribute.to_string();
+    let reparsed = SdpAttribute::from_str(&serialized);
+    prop_assert!(reparsed.is_ok(), "failed to reparse '{}'", serialized);
+    let reparsed = reparsed.unwrap();
+    prop_assert_eq!(reparsed.to_string(), serialized.clone());
+    prop_assert!(
+        reparsed == attribute,
+        "'{}' changed when reparsed",
+        serialized
+    );
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn candidate_roundtrip(
+        foundation in "[a-zA-Z0-9+/]{1,32}",
+        component in 1u32..=256,
+        tcp in any::<bool>(),
+        priority in any::<u32>(),
+        address in any::<IpAddr>(),
+        port in any::<u16>(),
+        related in proptest::option::of((any::<IpAddr>(), any::<u16>())),
+        generation in proptest::option::of(any::<u32>()),
+        ufrag in proptest::option::of("[a-zA-Z0-9+/]{4,32}"),
+        network_id in proptest::option::of(any::<u16>()),
+        network_cost in proptest::option::of(any::<u16>()),
+    ) {
+        let transport = if tcp {
+            SdpAttributeCandidateTransport::Tcp
+        } else {
+            SdpAttributeCandidateTransport::Udp
+        };
+        let c_type = if related.is_some() {
+            SdpAttributeCandidateType::Srflx
+        } else {
+            SdpAttributeCandidateType::Host
+        };
+        let mut candidate = SdpAttributeCandidate::new(
+            foundation,
+            component,
+            transport,
+            priority,
+            Address::Ip(address),
+            u32::from(port),
+            c_type,
+        );
+        if let Some((raddr, rport)) = related {
+            candidate.raddr = Some(Address::Ip(raddr));
+            candidate.rport = Some(u32::from(rport));
+        }
+        candidate.generation = generation;
+        candidate.ufrag = ufrag;
+        candidate.networkid = network_id;
+        candidate.networkcost = network_cost;
+        check_roundtrip(SdpAttribute::Candidate(candidate))?;
+    }
+
+    #[test]
+    fn rtpmap_roundtrip(
+        payload_type in 0u8..=127,
+        codec_name in "[a-zA-Z][a-zA-Z0-9-]{0,15}",
+        frequency in proptest::option::of(any::<u32>()),
+        channels in proptest::option::of(any::<u32>()),
+    ) {
+        let rtpmap = SdpAttributeRtpmap {
+            payload_type,
+            codec_name,
+            frequency,
+            // Channels can only be serialized after a clock rate
+            channels: frequency.and(channels),
+        };
+        check_roundtrip(SdpAttribute::Rtpmap(rtpmap))?;
+    }
+
+    #[test]
+    fn fmtp_roundtrip(
+        payload_type in 0u8..=127,
+        // Without the typed parameters the block may only consist of bare
+        // flags
+        typed in proptest::option::of((
+            0u32..=0x00ff_ffff,
+            0u32..=2,
+            any::<u32>(),
+            any::<u32>(),
+            any::<bool>(),
+            any::<bool>(),
+        )),
+        unknown in proptest::collection::vec(
+            ("x-[a-z]{1,8}", proptest::option::of("[a-zA-Z0-9]{1,8}")),
+            0..4,
+        ),
+    ) {
+        let mut parameters = SdpAttributeFmtpParameters {
+            unknown_tokens: unknown
+                .into_iter()
+                .map(|(name, value)| match value {
+                    Some(value) => format!("{}={}", name, value),
+                    None => name,
+                })
+                .collect(),
+            ..Default::default()
+        };
+        if let Some((profile_level_id, packetization_mode, max_fs, maxplaybackrate, stereo, useinbandfec)) = typed {
+            parameters.profile_level_id = profile_level_id;
+            parameters.packetization_mode = packetization_mode;
+            parameters.max_fs = max_fs;
+            parameters.maxplaybackrate = maxplaybackrate;
+            parameters.stereo = stereo;
+            parameters.useinbandfec = useinbandfec;
+        }
+        // An fmtp needs at least one parameter which differs from the defaults
+        prop_assume!(!parameters.to_string().is_empty());
+        let fmtp = SdpAttributeFmtp::from_parameters(SdpAttributePayloadType::PayloadType(payload_type), parameters);
+        check_roundtrip(SdpAttribute::Fmtp(fmtp))?;
+    }
+
+    #[test]
+    fn rtcp_roundtrip(
+        port in any::<u16>(),
+        address in proptest::option::of(any::<IpAddr>()),
+    ) {
+        let mut rtcp = SdpAttributeRtcp::new(port);
+        rtcp.unicast_addr = address.map(ExplicitlyTypedAddress::Ip);
+        check_roundtrip(SdpAttribute::Rtcp(rtcp))?;
+    }
+
+    #[test]
+    fn mid_roundtrip(mid in "[a-zA-Z0-9_-]{1,32}") {
+        check_roundtrip(SdpAttribute::Mid(mid))?;
+    }
+}